@@ -0,0 +1,151 @@
+use proc_macro2::TokenStream;
+use syn::{ext::IdentExt as _, DeriveInput, Error, Ident, Lit, LitStr, Meta, MetaNameValue};
+
+/// A case convention for `#[seed_rename_all = "..."]`, with the same names as serde's `rename_all`.
+#[derive(Clone, Copy)]
+pub enum RenameRule {
+	Lower,
+	Upper,
+	Pascal,
+	Camel,
+	Snake,
+	ScreamingSnake,
+	Kebab,
+	ScreamingKebab,
+}
+
+const RULES: &[(&str, RenameRule)] = &[
+	("lowercase", RenameRule::Lower),
+	("UPPERCASE", RenameRule::Upper),
+	("PascalCase", RenameRule::Pascal),
+	("camelCase", RenameRule::Camel),
+	("snake_case", RenameRule::Snake),
+	("SCREAMING_SNAKE_CASE", RenameRule::ScreamingSnake),
+	("kebab-case", RenameRule::Kebab),
+	("SCREAMING-KEBAB-CASE", RenameRule::ScreamingKebab),
+];
+
+impl RenameRule {
+	/// Renames a `snake_case` field.
+	pub fn apply_to_field(self, field: &str) -> String {
+		match self {
+			RenameRule::Lower | RenameRule::Snake => field.to_owned(),
+			RenameRule::Upper | RenameRule::ScreamingSnake => field.to_ascii_uppercase(),
+			RenameRule::Pascal => {
+				let mut pascal = String::new();
+				let mut capitalize = true;
+				for ch in field.chars() {
+					if ch == '_' {
+						capitalize = true;
+					} else if capitalize {
+						pascal.push(ch.to_ascii_uppercase());
+						capitalize = false;
+					} else {
+						pascal.push(ch);
+					}
+				}
+				pascal
+			}
+			RenameRule::Camel => lower_first(&RenameRule::Pascal.apply_to_field(field)),
+			RenameRule::Kebab => field.replace('_', "-"),
+			RenameRule::ScreamingKebab => field.to_ascii_uppercase().replace('_', "-"),
+		}
+	}
+
+	/// Renames a `PascalCase` variant.
+	pub fn apply_to_variant(self, variant: &str) -> String {
+		match self {
+			RenameRule::Pascal => variant.to_owned(),
+			RenameRule::Lower => variant.to_ascii_lowercase(),
+			RenameRule::Upper => variant.to_ascii_uppercase(),
+			RenameRule::Camel => lower_first(variant),
+			RenameRule::Snake => {
+				let mut snake = String::new();
+				for (i, ch) in variant.char_indices() {
+					if i > 0 && ch.is_uppercase() {
+						snake.push('_');
+					}
+					snake.push(ch.to_ascii_lowercase());
+				}
+				snake
+			}
+			RenameRule::ScreamingSnake => RenameRule::Snake
+				.apply_to_variant(variant)
+				.to_ascii_uppercase(),
+			RenameRule::Kebab => RenameRule::Snake
+				.apply_to_variant(variant)
+				.replace('_', "-"),
+			RenameRule::ScreamingKebab => RenameRule::ScreamingSnake
+				.apply_to_variant(variant)
+				.replace('_', "-"),
+		}
+	}
+}
+
+/// The default wire name of a named field.
+pub fn field_name(ident: &Ident, rule: Option<RenameRule>) -> LitStr {
+	let name = ident.unraw().to_string();
+	let name = rule.map_or(name.clone(), |rule| rule.apply_to_field(&name));
+	LitStr::new(&name, ident.span())
+}
+
+/// The wire name of a variant.
+pub fn variant_name(ident: &Ident, rule: Option<RenameRule>) -> LitStr {
+	let name = ident.unraw().to_string();
+	let name = rule.map_or(name.clone(), |rule| rule.apply_to_variant(&name));
+	LitStr::new(&name, ident.span())
+}
+
+fn lower_first(name: &str) -> String {
+	let mut chars = name.chars();
+	chars
+		.next()
+		.map(|first| first.to_lowercase().chain(chars).collect())
+		.unwrap_or_default()
+}
+
+/// Reads `#[seed_rename_all = "..."]`.
+pub fn parse_rename_all(input: &DeriveInput, errors: &mut Vec<TokenStream>) -> Option<RenameRule> {
+	let mut rule = None;
+	for attr in input
+		.attrs
+		.iter()
+		.filter(|a| a.path.is_ident("seed_rename_all"))
+	{
+		match attr.parse_meta() {
+			Ok(Meta::NameValue(MetaNameValue {
+				lit: Lit::Str(value),
+				..
+			})) => {
+				if rule.is_some() {
+					errors.push(
+						Error::new_spanned(attr, "Duplicate #[seed_rename_all]").to_compile_error(),
+					);
+				}
+				match RULES.iter().find(|(name, _)| value.value() == *name) {
+					Some((_, found)) => rule = Some(*found),
+					None => errors.push(
+						Error::new_spanned(
+							value,
+							format!(
+								"Unknown case convention, expected one of: {}",
+								RULES
+									.iter()
+									.map(|(name, _)| format!("{:?}", name))
+									.collect::<Vec<_>>()
+									.join(", ")
+							),
+						)
+						.to_compile_error(),
+					),
+				}
+			}
+			Ok(_) => errors.push(
+				Error::new_spanned(attr, "Expected #[seed_rename_all = \"...\"]")
+					.to_compile_error(),
+			),
+			Err(error) => errors.push(error.to_compile_error()),
+		}
+	}
+	rule
+}
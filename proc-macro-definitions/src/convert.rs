@@ -0,0 +1,130 @@
+use crate::field::Seeder;
+use proc_macro2::TokenStream;
+use quote::ToTokens as _;
+use syn::{
+	parenthesized,
+	parse::{ParseStream, Parser as _},
+	punctuated::Punctuated,
+	spanned::Spanned as _,
+	token, DeriveInput, Error, Expr, Ident, Path, Token, Type,
+};
+
+/// A conversion through an intermediate type, from `#[seed_from(...)]`, `#[seed_try_from(...)]` or `#[seeded_into(...)]`.
+pub struct Conversion {
+	/// The intermediate type that's (de)serialized instead.
+	pub raw: Type,
+	/// The conversion function, which also receives the seed arguments
+	/// (by reference for `seeded_into`, since the view only lends them).
+	pub with: Path,
+	/// Whether `with` returns a [`Result`].
+	pub fallible: bool,
+	/// How `raw` is (de)serialized, like for a field:
+	/// Through its own serde implementation unless `seeded`, `forward` or `seeder = expr` is given.
+	pub seeder: Seeder,
+}
+
+/// Reads `#[attr(Raw, with = path)]`, optionally followed by `seeded`, `forward(...)` or `seeder = expr`,
+/// for each attribute name in `attr_names` (paired with whether the conversion is fallible).
+pub fn parse(
+	input: &DeriveInput,
+	attr_names: &[(&str, bool)],
+	errors: &mut Vec<TokenStream>,
+) -> Option<Conversion> {
+	let mut conversion = None;
+	for (attr, fallible) in input.attrs.iter().filter_map(|a| {
+		attr_names
+			.iter()
+			.find(|(name, _)| a.path.is_ident(name))
+			.map(|(_, fallible)| (a, *fallible))
+	}) {
+		let parsed = (|input: ParseStream| {
+			let content;
+			let paren = parenthesized!(content in input);
+			let raw: Type = content.parse()?;
+			let mut with = None;
+			let mut seeder = Seeder::Plain;
+			while !content.is_empty() {
+				content.parse::<Token![,]>()?;
+				if content.is_empty() {
+					break;
+				}
+				let key: Ident = content.parse()?;
+				match key.to_string().as_str() {
+					"with" if with.is_none() => {
+						content.parse::<Token![=]>()?;
+						with = Some(content.parse::<Path>()?);
+					}
+					"seeded" if matches!(seeder, Seeder::Plain) => {
+						seeder = Seeder::Inherent { span: key.span() }
+					}
+					"forward" if matches!(seeder, Seeder::Plain) => {
+						let args = if content.peek(token::Paren) {
+							let names;
+							parenthesized!(names in content);
+							Some(
+								Punctuated::<Ident, Token![,]>::parse_terminated(&names)?
+									.into_iter()
+									.collect(),
+							)
+						} else {
+							None
+						};
+						seeder = Seeder::Forward {
+							span: key.span(),
+							args,
+						}
+					}
+					"seeder" if matches!(seeder, Seeder::Plain) => {
+						content.parse::<Token![=]>()?;
+						seeder = Seeder::Custom {
+							span: paren.span,
+							seeder: content.parse::<Expr>()?.into_token_stream(),
+						};
+					}
+					"with" | "seeded" | "forward" | "seeder" => {
+						return Err(Error::new_spanned(
+							&key,
+							"Duplicate `with` or seeder in conversion",
+						))
+					}
+					_ => {
+						return Err(Error::new_spanned(
+							key,
+							"Expected `with = path`, `seeded`, `forward` or `seeder = expr`",
+						))
+					}
+				}
+			}
+			match with {
+				Some(with) => Ok(Conversion {
+					raw,
+					with,
+					fallible,
+					seeder,
+				}),
+				None => Err(Error::new(
+					paren.span,
+					"Expected a conversion function: `with = path`",
+				)),
+			}
+		})
+		.parse2(attr.tokens.clone());
+
+		match parsed {
+			Ok(parsed) => {
+				if conversion.is_some() {
+					errors.push(
+						Error::new(
+							attr.span(),
+							"Only one conversion can be given per direction",
+						)
+						.to_compile_error(),
+					);
+				}
+				conversion = Some(parsed);
+			}
+			Err(error) => errors.push(error.to_compile_error()),
+		}
+	}
+	conversion
+}
@@ -1,36 +1,76 @@
-use crate::serde_seeded;
+use crate::{
+	case::{self, RenameRule},
+	clone_where,
+	convert::{self, Conversion},
+	field::{self, Direction, FieldAttrs, Seeder},
+	forwarded_args, generic_param_ident, hook_paths, name_elided_lifetimes,
+	profile::{self, Profile},
+	refers_to, serde_seeded,
+	tagging::{self, Tagging},
+	unknown_fields::{self, UnknownFields},
+	used_args,
+	version::{self, FieldVersions, Migration, Version, VersionSource},
+	visibility_and_name, without_default,
+};
 use call2_for_syn::call2;
 use proc_macro2::{Span, TokenStream};
 use quote::{quote, quote_spanned, ToTokens};
-use std::borrow::Cow;
 use syn::{
-	parenthesized, parse2,
+	parenthesized, parse2, parse_quote,
 	punctuated::{Pair, Punctuated},
 	spanned::Spanned as _,
-	Data, DeriveInput, Error, FnArg, GenericParam, Generics, Ident, Lifetime, PatType, Token,
+	Data, DeriveInput, Error, Expr, Fields, FnArg, GenericParam, Generics, Ident, Lifetime,
+	LifetimeDef, LitByteStr, LitStr, PatType, Path, Token, Type, TypeReference, Visibility,
+	WherePredicate,
 };
 use wyz::TapOption as _;
 
 pub fn expand_derive(input: &DeriveInput) -> syn::Result<TokenStream> {
-	let name = &input.ident;
-	let serde_seeded = serde_seeded();
 	let mut errors = vec![];
-
-	let mut type_generics_types = vec![];
-	for generic in input.generics.params.iter() {
-		match generic {
-			GenericParam::Lifetime(l) => {errors.push(Error::new_spanned(l, "serde-seeded::seed: Lifetime parameters are currently not supported here. You can request or help out with implementation at <https://github.com/Tamschi/serde-seeded/issues/1>.").to_compile_error())}
-			GenericParam::Type(ty) => type_generics_types.push(ty),
-			GenericParam::Const(c) => {errors.push(Error::new_spanned(c, "serde-seeded::seed: Const parameters are currently not supported here. You can request or help out with implementation at <https://github.com/Tamschi/serde-seeded/issues/2>.").to_compile_error())}
-		}
+	let profiles = profile::parse(input, &mut errors);
+	// Only the seed arguments and seeders differ between profiles, so everything else is read (and checked) once.
+	let shared = parse_shared(input, &profiles, &mut errors)?;
+	if profiles.is_empty() {
+		let items = expand(input, &shared, None);
+		return Ok(quote!(#(#errors)* #items));
 	}
 
-	let type_generics_type_idents = type_generics_types
+	if let Some(attr) = input.attrs.iter().find(|a| a.path.is_ident("seed_type")) {
+		errors.push(
+			Error::new_spanned(attr, "#[seed_type] can't be combined with #[seed_profile]")
+				.to_compile_error(),
+		);
+	}
+	let items = profiles
 		.iter()
-		.map(|t| &t.ident)
-		.collect::<Vec<_>>();
-	let type_generics_where = &input.generics.where_clause;
+		.map(|profile| expand(input, &shared, Some(profile)));
+	Ok(quote!(#(#errors)* #(#items)*))
+}
 
+/// The input's attributes, which all profiles share.
+struct Shared {
+	fn_generics: Vec<Generics>,
+	/// The `#[seed_args]`, to which a profile adds its own.
+	args: Vec<FnArg>,
+	fn_where: Vec<WherePredicate>,
+	version: Option<Version>,
+	validators: Vec<Path>,
+	finish: Option<Path>,
+	rename_all: Option<RenameRule>,
+	unknown_fields: UnknownFields,
+	tagging: Tagging,
+	conversion: Option<Conversion>,
+	seed_fn: Option<(Visibility, Ident)>,
+	seed_type: Option<(Visibility, Ident)>,
+	/// The attributes of the struct's fields, or of each variant's.
+	fields: Vec<Vec<FieldAttrs>>,
+}
+
+fn parse_shared(
+	input: &DeriveInput,
+	profiles: &[Profile],
+	errors: &mut Vec<TokenStream>,
+) -> syn::Result<Shared> {
 	let fn_generics = input
 		.attrs
 		.iter()
@@ -47,25 +87,202 @@ pub fn expand_derive(input: &DeriveInput) -> syn::Result<TokenStream> {
 		})
 		.collect::<Vec<_>>();
 
+	let mut args = input
+		.attrs
+		.iter()
+		.filter(|a| a.path.is_ident("seed_args") || a.path.is_ident("seed_args_de"))
+		.filter_map(|a| {
+			call2(a.tokens.clone(), |input| {
+				let args;
+				parenthesized!(args in input);
+				let args = Punctuated::<FnArg, Token![,]>::parse_terminated(&args)?
+					.into_pairs()
+					.map(Pair::into_value);
+				Ok(args)
+			})
+			.map_err(|e| errors.push(e.to_compile_error()))
+			.ok()
+		})
+		.flatten()
+		.collect::<Vec<_>>();
+	args.retain(|arg| match arg {
+		FnArg::Receiver(r) => {
+			errors.push(
+				Error::new_spanned(r, "self-parameters are not supported in this position")
+					.to_compile_error(),
+			);
+			false
+		}
+		FnArg::Typed(_) => true,
+	});
+
+	let fn_where = input
+		.attrs
+		.iter()
+		.filter(|a| a.path.is_ident("seed_where") || a.path.is_ident("seed_where_de"))
+		.filter_map(|a| {
+			call2(a.tokens.clone(), |input| {
+				let predicates;
+				parenthesized!(predicates in input);
+				let predicates =
+					Punctuated::<WherePredicate, Token![,]>::parse_terminated(&predicates)?
+						.into_pairs()
+						.map(Pair::into_value);
+				Ok(predicates)
+			})
+			.map_err(|e| errors.push(e.to_compile_error()))
+			.ok()
+		})
+		.flatten()
+		.collect::<Vec<_>>();
+
+	let version = version::parse(input, errors);
+	let validators = hook_paths(input, "seed_validate", errors);
+	let mut finish = hook_paths(input, "seed_finish", errors).into_iter();
+	let first_finish = finish.next();
+	errors.extend(
+		finish.map(|path| Error::new_spanned(path, "Duplicate #[seed_finish]").to_compile_error()),
+	);
+	let rename_all = case::parse_rename_all(input, errors);
+	let unknown_fields = unknown_fields::parse(input, errors);
+	let tagging = tagging::parse(input, errors);
+	let conversion = convert::parse(
+		input,
+		&[("seed_from", false), ("seed_try_from", true)],
+		errors,
+	);
+	let seed_fn = visibility_and_name(input, "seed_fn", errors);
+	let seed_type = visibility_and_name(input, "seed_type", errors);
+
+	// Seeders that mention a seed argument mean that rather than a field of the same name.
+	let arg_idents = forwarded_args(&args, None)
+		.into_iter()
+		.chain(
+			profiles
+				.iter()
+				.flat_map(|profile| forwarded_args(&profile.args, None)),
+		)
+		.filter_map(|arg| arg.ok().map(|(ident, _)| ident.clone()))
+		.collect::<Vec<_>>();
+	let fields = match &input.data {
+		Data::Struct(data) => vec![parse_fields(
+			&data.fields,
+			profiles,
+			version.as_ref(),
+			&arg_idents,
+			errors,
+		)],
+		Data::Enum(data) => {
+			let mut fields = vec![];
+			for variant in data.variants.iter() {
+				errors.extend(
+					variant.attrs.iter()
+						.filter(|a| a.path.is_ident("seeded") || a.path.is_ident("seeded_de"))
+						.map(|a| Error::new_spanned(a, "Misplaced #[seeded] or #[seeded_de]: This attribute is only valid on fields")
+							.to_compile_error()
+						)
+					);
+
+				let attrs = parse_fields(&variant.fields, profiles, version.as_ref(), &arg_idents, errors);
+				errors.extend(variant.fields.iter().zip(attrs.iter()).filter(|(_, f)| f.flatten).map(|(field, _)| {
+					Error::new_spanned(field, "Fields of enum variants can't be flattened").to_compile_error()
+				}));
+				if let (Tagging::Internal { .. }, Fields::Unnamed(unnamed)) = (&tagging, &variant.fields) {
+					if unnamed.unnamed.len() != 1 {
+						errors.push(Error::new_spanned(variant, "Tuple variants are not supported in internally tagged enums").to_compile_error());
+					}
+				}
+				fields.push(attrs);
+			}
+			fields
+		}
+		Data::Union(u) => return Err(Error::new_spanned(u.union_token, "serde-seeded derive macros are not available on unions yet. You can request this feature at <https://github.com/Tamschi/serde-seeded/issues/6>.")),
+	};
+
+	Ok(Shared {
+		fn_generics,
+		args,
+		fn_where,
+		version,
+		validators,
+		finish: first_finish,
+		rename_all,
+		unknown_fields,
+		tagging,
+		conversion,
+		seed_fn,
+		seed_type,
+		fields,
+	})
+}
+
+/// Expands the derive once, for the given profile or for none.
+fn expand(input: &DeriveInput, shared: &Shared, profile: Option<&Profile>) -> TokenStream {
+	let name = &input.ident;
+	let serde_seeded = serde_seeded();
+
+	let mut type_generics_lifetimes = vec![];
+	let mut type_generics_params = vec![];
+	for generic in input.generics.params.iter() {
+		match generic {
+			GenericParam::Lifetime(l) => type_generics_lifetimes.push(l),
+			GenericParam::Type(_) | GenericParam::Const(_) => {
+				type_generics_params.push(without_default(generic))
+			}
+		}
+	}
+
+	let type_generics_lifetime_lifetimes = type_generics_lifetimes
+		.iter()
+		.map(|l| &l.lifetime)
+		.collect::<Vec<_>>();
+	let type_generics_param_idents = type_generics_params
+		.iter()
+		.map(generic_param_ident)
+		.collect::<Vec<_>>();
+	// Const parameters can't appear in `PhantomData`, but also don't need to.
+	let type_generics_type_idents = type_generics_params
+		.iter()
+		.filter_map(|p| match p {
+			GenericParam::Type(ty) => Some(&ty.ident),
+			_ => None,
+		})
+		.collect::<Vec<_>>();
+	let type_generics_where = &input.generics.where_clause;
+
+	let mut args = shared.args.clone();
+	if let Some(profile) = profile {
+		args.extend(profile.args.iter().cloned());
+	}
+	let mut elided = vec![];
+	name_elided_lifetimes(&mut args, &mut |span| {
+		let lifetime = Lifetime::new(&format!("'__arg{}", elided.len()), span);
+		elided.push(LifetimeDef::new(lifetime.clone()));
+		lifetime
+	});
+
 	let mut fn_generics_lifetimes = vec![];
-	let mut fn_generics_types = vec![];
-	for generic in fn_generics.iter().flat_map(|g| g.params.iter()) {
+	let mut fn_generics_params = vec![];
+	for generic in shared.fn_generics.iter().flat_map(|g| g.params.iter()) {
 		match generic {
-			GenericParam::Type(ty) => fn_generics_types.push(ty),
-			GenericParam::Lifetime(l) => fn_generics_lifetimes.push(l),
-			GenericParam::Const(c) => {errors.push(Error::new_spanned(c, "serde-seeded::seed: Const parameters are currently not supported here. You can request or help out with implementation at <https://github.com/Tamschi/serde-seeded/issues/3>.").to_compile_error())}
+			GenericParam::Lifetime(l) => fn_generics_lifetimes.push(l.clone()),
+			GenericParam::Type(_) | GenericParam::Const(_) => {
+				fn_generics_params.push(without_default(generic))
+			}
 		}
 	}
+	fn_generics_lifetimes.extend(elided);
 
 	let fn_generics_lifetime_lifetimes = fn_generics_lifetimes
 		.iter()
 		.map(|l| &l.lifetime)
 		.collect::<Vec<_>>();
-	let fn_generics_type_idents = fn_generics_types
+	let fn_generics_param_idents = fn_generics_params
 		.iter()
-		.map(|t| &t.ident)
+		.map(generic_param_ident)
 		.collect::<Vec<_>>();
-	// Where clauses on derived functions are missing too but don't have a specific error since there's no syntax to specify them yet. The GitHub issue is <https://github.com/Tamschi/serde-seeded/issues/4>.
+
+	let mut fn_where = shared.fn_where.clone();
 
 	let mut default_de = vec![Lifetime::new("'de", Span::mixed_site())];
 	let de = fn_generics_lifetime_lifetimes
@@ -75,226 +292,1831 @@ pub fn expand_derive(input: &DeriveInput) -> syn::Result<TokenStream> {
 		.tap_some(|_| default_de.pop().unwrap())
 		.unwrap_or_else(|| default_de.first().unwrap());
 
-	let args = input
-		.attrs
+	// The fields of a converted type aren't read themselves.
+	if shared.conversion.is_none() {
+		fn_where.extend(field::plain_bounds(
+			input,
+			&shared.fields,
+			profile,
+			&quote!(#serde_seeded::serde::Deserialize<#de>),
+		));
+	}
+
+	// Like with serde_derive, the input must outlive the resulting value so that it can borrow from it.
+	let de_outlives = if type_generics_lifetime_lifetimes.is_empty() {
+		vec![]
+	} else {
+		vec![quote!(#de: #(#type_generics_lifetime_lifetimes)+*)]
+	};
+	let seed_where = {
+		let predicates = de_outlives.iter().cloned().chain(
+			type_generics_where
+				.iter()
+				.flat_map(|w| w.predicates.iter())
+				.chain(fn_where.iter())
+				.map(ToTokens::into_token_stream),
+		);
+		quote!(where #(#predicates,)*)
+	};
+
+	let arg_names = args
 		.iter()
-		.filter(|a| a.path.is_ident("seed_args") || a.path.is_ident("seed_args_de"))
-		.filter_map(|a| {
-			call2(a.tokens.clone(), |input| {
-				let args;
-				parenthesized!(args in input);
-				let args = Punctuated::<FnArg, Token![,]>::parse_terminated(&args)?
-					.into_pairs()
-					.map(Pair::into_value);
-				Ok(args)
-			})
-			.map_err(|e| errors.push(e.to_compile_error()))
-			.ok()
+		.filter_map(|arg| match arg {
+			FnArg::Receiver(_) => None,
+			FnArg::Typed(PatType { pat, .. }) => Some(pat),
 		})
-		.flatten()
 		.collect::<Vec<_>>();
 
-	let arg_names = args
+	let self_ty = quote! {
+		#name<
+			#(#type_generics_lifetime_lifetimes,)*
+			#(#type_generics_param_idents,)*
+		>
+	};
+	// With a format version, every carrier below also holds the version of the input as `version`.
+	// The seed itself doesn't, since it may only find out about it while deserializing.
+	let version = &shared.version;
+	let (version_field, version_binding) = match version {
+		Some(_) => (
+			quote_spanned!(Span::mixed_site()=> __version: u32,),
+			quote_spanned!(Span::mixed_site()=> __version: version,),
+		),
+		None => (quote!(), quote!()),
+	};
+	let seed_take_args = quote_spanned! {Span::mixed_site()=>
+		let Self {
+			#(#arg_names,)*
+			limiter: _,
+		} = self;
+	};
+	let seed_give_args = quote_spanned! {Span::mixed_site()=>
+		#(#arg_names,)*
+		limiter: ::std::marker::PhantomData,
+	};
+	// Not every visitor method uses every argument the attributes do.
+	let arg_idents = forwarded_args(&args, None)
+		.into_iter()
+		.filter_map(|arg| arg.ok().map(|(ident, _)| ident))
+		.collect::<Vec<_>>();
+	let arg_idents = used_args(input, &arg_idents);
+	let take_args = quote_spanned! {Span::mixed_site()=>
+		let Self {
+			#(#arg_names,)*
+			#version_binding
+			limiter: _,
+		} = self;
+		#(let _ = &#arg_idents;)*
+	};
+	let give_args = quote_spanned! {Span::mixed_site()=>
+		#(#arg_names,)*
+		#version_binding
+		limiter: ::std::marker::PhantomData,
+	};
+	// Expressions that lend the seed arguments to a hook,
+	// by reborrowing mutable references, copying shared ones and borrowing anything else.
+	let lent_args = args
 		.iter()
-		.map(|arg| match arg {
-			FnArg::Receiver(r) => {
-				Error::new_spanned(r, "self-parameters are not supported in this position")
-					.to_compile_error()
+		.filter_map(|arg| match arg {
+			FnArg::Receiver(_) => None,
+			FnArg::Typed(PatType { pat, ty, .. }) => Some(match &**ty {
+				Type::Reference(TypeReference {
+					mutability: Some(_),
+					..
+				}) => quote_spanned!(Span::mixed_site()=> &mut *#pat),
+				Type::Reference(_) => quote_spanned!(Span::mixed_site()=> #pat),
+				_ => quote_spanned!(Span::mixed_site()=> &#pat),
+			}),
+		})
+		.collect::<Vec<_>>();
+	// Checks a deserialized field's value, with the seed arguments in scope.
+	let field_validation = |validate: &Expr, local: &Ident| {
+		quote_spanned! {Span::mixed_site()=>
+			#validate(&#local, #(#lent_args),*).map_err(de::Error::custom)?;
+		}
+	};
+
+	// The steps after a value is read run where it's constructed, since the seed arguments are available there.
+	let migrations = version
+		.iter()
+		.flat_map(|version| version.migrations.iter())
+		.map(|Migration { from, with }| {
+			quote_spanned! {Span::mixed_site()=>
+				if version <= #from {
+					#serde_seeded::log::trace!("Migrating {} from version {}...", stringify!(#name), #from);
+					#with(&mut value, #(#lent_args),*).map_err(de::Error::custom)?;
+				}
+			}
+		})
+		.collect::<Vec<_>>();
+	let validations = shared
+		.validators
+		.iter()
+		.map(|validate| {
+			quote_spanned! {Span::mixed_site()=>
+				#validate(&value, #(#lent_args),*).map_err(de::Error::custom)?;
 			}
-			FnArg::Typed(PatType { pat, .. }) => pat.into_token_stream(),
 		})
 		.collect::<Vec<_>>();
+	// The finishing step is last, so it gets to keep the seed arguments.
+	let finish = shared.finish.as_ref().map(|finish| {
+		quote_spanned! {Span::mixed_site()=>
+			#finish(value, #(#arg_names),*).map_err(de::Error::custom)
+		}
+	});
+	// Turns the expression `construct` for a freshly read value into the visitor's result.
+	let complete = |construct: TokenStream| {
+		if migrations.is_empty() && validations.is_empty() && finish.is_none() {
+			return quote_spanned!(Span::mixed_site()=> ::std::result::Result::Ok(#construct));
+		}
+		let binding = if migrations.is_empty() {
+			quote_spanned!(Span::mixed_site()=> value)
+		} else {
+			quote_spanned!(Span::mixed_site()=> mut value)
+		};
+		let finish = finish.clone().unwrap_or_else(
+			|| quote_spanned!(Span::mixed_site()=> ::std::result::Result::Ok(value)),
+		);
+		quote_spanned! {Span::mixed_site()=>
+			{
+				let #binding = #construct;
+				#(#migrations)*
+				#(#validations)*
+				#finish
+			}
+		}
+	};
 
-	match &input.data {
-		Data::Struct(data) => {
-			let mut field_idents = vec![];
-			let mut nexts = vec![];
+	// Declares a struct that carries the seed arguments (plus `fields`) and implements `trait_` with `body`.
+	let carrier =
+		|ident: TokenStream, fields: TokenStream, trait_: TokenStream, body: TokenStream| {
+			quote_spanned! {Span::mixed_site()=>
+				struct #ident<
+					#(#fn_generics_lifetimes,)*
+					#(#type_generics_lifetimes,)*
+					#(#type_generics_params,)*
+					#(#fn_generics_params,)*
+				> {
+					#(#args,)*
+					#version_field
+					#fields
+					limiter: ::std::marker::PhantomData<(
+						#(&#fn_generics_lifetime_lifetimes (),)*
+						#(&#type_generics_lifetime_lifetimes (),)*
+						#(#type_generics_type_idents,)*
+					)>,
+				};
+				impl<
+					#(#default_de,)*
+					#(#fn_generics_lifetimes,)*
+					#(#type_generics_lifetimes,)*
+					#(#type_generics_params,)*
+					#(#fn_generics_params,)*
+				> #trait_ for #ident<
+					#(#fn_generics_lifetime_lifetimes,)*
+					#(#type_generics_lifetime_lifetimes,)*
+					#(#type_generics_param_idents,)*
+					#(#fn_generics_param_idents,)*
+				> #seed_where {
+					#body
+				}
+			}
+		};
+
+	// Declares a `Visitor` that carries the seed arguments, to be used in the following statements.
+	let visitor = |body: TokenStream| {
+		carrier(
+			quote!(Visitor),
+			quote!(),
+			quote_spanned!(Span::mixed_site()=> de::Visitor<#de>),
+			quote_spanned! {Span::mixed_site()=>
+				type Value = #self_ty;
+				#body
+			},
+		)
+	};
+
+	let rename_all = shared.rename_all;
+	let unknown_fields = &shared.unknown_fields;
 
-			for (i, field) in data.fields.iter().enumerate() {
-				field_idents.push(
-					field
-						.ident
+	// Declares a `Visitor` that deserializes the given fields from a sequence or (if named) a map,
+	// plus the `FIELD_NAMES` and field identifier those need.
+	// `container` is what kind of struct or variant the fields belong to, for error messages,
+	// and `complete` turns the constructed value into the result.
+	let fields_visitor = |container: &str,
+	                      label: &LitStr,
+	                      path: TokenStream,
+	                      fields: &Fields,
+	                      seeded: &[SeededField],
+	                      complete: &dyn Fn(TokenStream) -> TokenStream| {
+		let construct = {
+			let idents = seeded.iter().map(|f| f.ident);
+			let locals = seeded.iter().map(|f| &f.local);
+			complete(match fields {
+				Fields::Named(_) => quote!(#path { #(#idents: #locals,)* }),
+				Fields::Unnamed(_) => quote!(#path(#(#locals,)*)),
+				Fields::Unit => path,
+			})
+		};
+		let validations = seeded
+			.iter()
+			.filter_map(
+				|SeededField {
+				     local, validate, ..
+				 }| {
+					validate
 						.as_ref()
-						.map(Cow::Borrowed)
-						.unwrap_or_else(|| Cow::Owned(Ident::new(&i.to_string(), field.ty.span()))),
-				);
+						.map(|validate| field_validation(validate, local))
+				},
+			)
+			.collect::<Vec<_>>();
+		let skipped = seeded
+			.iter()
+			.filter_map(|SeededField { local, skip, .. }| {
+				skip.as_ref()
+					.map(|value| quote_spanned!(Span::mixed_site()=> let #local = #value;))
+			})
+			.collect::<Vec<_>>();
 
-				let mut attrs: Vec<_> = field
-					.attrs
+		// Only the fields that aren't skipped appear in the input,
+		// and flattened ones are read from the entries no other field claims.
+		let flattened = seeded
+			.iter()
+			.filter(|f| f.skip.is_none() && f.flatten)
+			.collect::<Vec<_>>();
+		let seeded = seeded
+			.iter()
+			.filter(|f| f.skip.is_none() && !f.flatten)
+			.collect::<Vec<_>>();
+		let locals = seeded.iter().map(|f| &f.local).collect::<Vec<_>>();
+		let local_of = |ident: &Ident| {
+			&seeded
+				.iter()
+				.find(|f| f.ident == Some(ident))
+				.unwrap()
+				.local
+		};
+		// Binds the value of `read` to the field's local. Only a seeder that depends on earlier fields
+		// sees their values under their own names, which are moved back into their locals after.
+		let bind = |field: &SeededField, read: TokenStream| {
+			let local = &field.local;
+			let dependencies = &field.dependencies;
+			if dependencies.is_empty() {
+				return quote_spanned!(Span::mixed_site()=> let #local = #read;);
+			}
+			let dependency_locals = dependencies
+				.iter()
+				.map(|ident| local_of(ident))
+				.collect::<Vec<_>>();
+			quote_spanned! {Span::mixed_site()=>
+				let (#local, #(#dependency_locals,)*) = {
+					#(let #dependencies = #dependency_locals;)*
+					(#read, #(#dependencies,)*)
+				};
+			}
+		};
+		let names = seeded.iter().map(|f| &f.name).collect::<Vec<_>>();
+		let seeds = seeded.iter().map(|f| &f.seed).collect::<Vec<_>>();
+		let len = seeded.len();
+		// With fields that only some versions have, the number of elements depends on the input's version.
+		let versioned_len = if seeded.iter().any(|f| f.versions.is_some()) {
+			Some(element_count(seeded.iter().copied()))
+		} else {
+			None
+		};
+		let prefix = format!("{} {}", container, label.value());
+
+		let expecting = match (fields, flattened.is_empty()) {
+			(Fields::Unit, _) | (_, false) => format!("{} {}", container, label.value()),
+			_ => format!(
+				"{} {} with {} element{}",
+				container,
+				label.value(),
+				len,
+				if len == 1 { "" } else { "s" }
+			),
+		};
+		let next_elements = seeded.iter().enumerate().map(|(i, field)| {
+			let SeededField { name, seed, default, versions, .. } = field;
+			let next = match default {
+				Some(default) => quote_spanned! {Span::mixed_site()=>
+					match seq.next_element_seed(#seed)? {
+						::std::option::Option::Some(value) => value,
+						::std::option::Option::None => #default,
+					}
+				},
+				None if versioned_len.is_some() => {
+					let index = element_count(seeded[..i].iter().copied());
+					quote_spanned! {Span::mixed_site()=>
+						match seq.next_element_seed(#seed)? {
+							::std::option::Option::Some(value) => value,
+							::std::option::Option::None => return ::std::result::Result::Err(de::Error::invalid_length(
+								#index,
+								&::std::format!("{}, but field `{}` is missing", __expecting, #name).as_str(),
+							)),
+						}
+					}
+				}
+				None => {
+					let missing = LitStr::new(
+						&format!("{}, but field `{}` is missing", expecting, name.value()),
+						Span::mixed_site(),
+					);
+					quote_spanned! {Span::mixed_site()=>
+						match seq.next_element_seed(#seed)? {
+							::std::option::Option::Some(value) => value,
+							::std::option::Option::None => return ::std::result::Result::Err(de::Error::invalid_length(#i, &#missing)),
+						}
+					}
+				}
+			};
+			match versions {
+				// Fields that aren't part of the input's version aren't read at all.
+				Some(versions) => {
+					let condition = versions.condition();
+					let absent = absent(default);
+					bind(field, quote_spanned!(Span::mixed_site()=> if #condition { #next } else { #absent }))
+				}
+				None => bind(field, next),
+			}
+		});
+		let expecting = LitStr::new(&expecting, Span::mixed_site());
+		let (count_elements, expected_len, expected) = match &versioned_len {
+			Some(count) => (
+				quote_spanned! {Span::mixed_site()=>
+					let __len: usize = #count;
+					let __expecting = ::std::format!("{} with {} element{}", #prefix, __len, if __len == 1 { "" } else { "s" });
+				},
+				quote_spanned!(Span::mixed_site()=> __len),
+				quote_spanned!(Span::mixed_site()=> __expecting.as_str()),
+			),
+			None => (quote!(), quote!(#len), quote!(#expecting)),
+		};
+		// Like with serde_derive, structs with flattened fields can only be read from maps.
+		let visit_seq = if !flattened.is_empty() {
+			quote!()
+		} else {
+			quote_spanned! {Span::mixed_site()=>
+				fn visit_seq<__A: de::SeqAccess<#de>>(self, mut seq: __A) -> Result<Self::Value, __A::Error> {
+					#take_args
+					#count_elements
+
+					#serde_seeded::log::trace!("Starting to deserialize {}...", #label);
+					#(#next_elements)*
+
+					// Trailing elements are rejected with the total length, like serde's own `SeqDeserializer` does.
+					let mut surplus = 0;
+					while let ::std::option::Option::Some(de::IgnoredAny) = seq.next_element()? {
+						surplus += 1;
+					}
+					if surplus > 0 {
+						return ::std::result::Result::Err(de::Error::invalid_length(#expected_len + surplus, &#expected));
+					}
+
+					#(#skipped)*
+					#(#validations)*
+					#serde_seeded::log::trace!("Done deserializing {}.", #label);
+
+					#construct
+				}
+			}
+		};
+
+		let (field_identifier, visit_map) = match fields {
+			Fields::Named(_) => {
+				let variants = (0..len)
+					.map(|i| Ident::new(&format!("__field{}", i), Span::mixed_site()))
+					.collect::<Vec<_>>();
+				let indices = (0..len as u64).collect::<Vec<_>>();
+				// Fields whose seeders refer to earlier fields are buffered if they arrive before those,
+				// and are then read in order once the map is exhausted.
+				let buffers = seeded
+					.iter()
+					.enumerate()
+					.filter(|(_, f)| !f.dependencies.is_empty())
+					.map(|(i, _)| Ident::new(&format!("__buffered{}", i), Span::mixed_site()))
+					.collect::<Vec<_>>();
+				// Seeds that don't need anything from within the loop are built once before it, in slots emptied as
+				// their keys arrive, so that a seeder may consume an argument. They're dropped with the loop.
+				let (slots, early_seeds): (Vec<_>, Vec<_>) = seeded
 					.iter()
-					.filter(|a| a.path.is_ident("seeded") || a.path.is_ident("seeded_de"))
-					.collect();
-
-				if attrs.len() > 1 {
-					let mut attrs = attrs.split_off(1).into_iter().map(|a| {
-						Error::new_spanned(
-							a,
-							"Multiple #[seeded] or #[seeded_de] attributes on the same field",
+					.enumerate()
+					.filter(|(_, f)| f.early)
+					.map(|(i, f)| {
+						(
+							Ident::new(&format!("__seed{}", i), Span::mixed_site()),
+							&f.seed,
 						)
-					});
-					let mut first = attrs.next().unwrap();
-					for next in attrs {
-						first.combine(next);
+					})
+					.unzip();
+				let mut remaining_slots = slots.iter();
+				let mut remaining_buffers = buffers.iter();
+				let read_values = seeded.iter().map(|SeededField { local, name, seed, dependencies, early, .. }| {
+					if *early {
+						let slot = remaining_slots.next().unwrap();
+						return quote_spanned! {Span::mixed_site()=>
+							match ::std::option::Option::take(&mut #slot) {
+								::std::option::Option::Some(seed) => #local = ::std::option::Option::Some(map.next_value_seed(seed)?),
+								::std::option::Option::None => return ::std::result::Result::Err(de::Error::duplicate_field(#name)),
+							}
+						};
 					}
-					errors.push(first.to_compile_error())
+					if dependencies.is_empty() {
+						return quote_spanned! {Span::mixed_site()=>
+							if ::std::option::Option::is_some(&#local) {
+								return ::std::result::Result::Err(de::Error::duplicate_field(#name));
+							}
+							#local = ::std::option::Option::Some(map.next_value_seed(#seed)?);
+						};
+					}
+					let buffer = remaining_buffers.next().unwrap();
+					let dependency_locals = dependencies.iter().map(|ident| local_of(ident)).collect::<Vec<_>>();
+					let temporaries = (0..dependencies.len())
+						.map(|i| Ident::new(&format!("__dependency{}", i), Span::mixed_site()))
+						.collect::<Vec<_>>();
+					quote_spanned! {Span::mixed_site()=>
+						if ::std::option::Option::is_some(&#local) || ::std::option::Option::is_some(&#buffer) {
+							return ::std::result::Result::Err(de::Error::duplicate_field(#name));
+						}
+						match (#(::std::option::Option::take(&mut #dependency_locals),)*) {
+							(#(::std::option::Option::Some(#temporaries),)*) => {
+								// Only this seeder sees the values of the fields it depends on, under their own names.
+								let (value, #(#temporaries,)*) = {
+									#(let #dependencies = #temporaries;)*
+									let value = map.next_value_seed(#seed)?;
+									(value, #(#dependencies,)*)
+								};
+								#local = ::std::option::Option::Some(value);
+								#(#dependency_locals = ::std::option::Option::Some(#temporaries);)*
+							}
+							(#(#temporaries,)*) => {
+								#(#dependency_locals = #temporaries;)*
+								#serde_seeded::log::trace!("Buffering field {} of {}...", #name, #label);
+								#buffer = ::std::option::Option::Some(map.next_value::<#serde_seeded::content::Content<#de>>()?);
+							}
+						}
+					}
+				}).collect::<Vec<_>>();
+				let mut remaining_buffers = buffers.iter();
+				let replay_buffered = seeded
+					.iter()
+					.map(|field| {
+						if field.dependencies.is_empty() {
+							return quote!();
+						}
+						let SeededField { local, seed, .. } = field;
+						let buffer = remaining_buffers.next().unwrap();
+						bind(
+							field,
+							quote_spanned! {Span::mixed_site()=>
+								match #buffer {
+									::std::option::Option::Some(content) => ::std::option::Option::Some(de::DeserializeSeed::deserialize(
+										#seed,
+										#serde_seeded::content::ContentRefDeserializer::<__A::Error>::new(&content),
+									)?),
+									::std::option::Option::None => #local,
+								}
+							},
+						)
+					})
+					.collect::<Vec<_>>();
+				let unwrap_values = seeded.iter().map(
+					|SeededField {
+					     local,
+					     name,
+					     default,
+					     versions,
+					     ..
+					 }| match (default, versions) {
+						(Some(default), _) => quote_spanned! {Span::mixed_site()=>
+							let #local = match #local {
+								::std::option::Option::Some(value) => value,
+								::std::option::Option::None => #default,
+							};
+						},
+						// Only fields that are part of the input's version are required.
+						(None, Some(versions)) => {
+							let condition = versions.condition();
+							let absent = absent(&None);
+							quote_spanned! {Span::mixed_site()=>
+								let #local = match #local {
+									::std::option::Option::Some(value) => value,
+									::std::option::Option::None if !#condition => #absent,
+									::std::option::Option::None => return ::std::result::Result::Err(de::Error::missing_field(#name)),
+								};
+							}
+						}
+						(None, None) => quote_spanned! {Span::mixed_site()=>
+							let #local = #local.ok_or_else(|| de::Error::missing_field(#name))?;
+						},
+					},
+				);
+				let name_patterns = seeded
+					.iter()
+					.map(|SeededField { name, aliases, .. }| quote!(#name #(| #aliases)*))
+					.collect::<Vec<_>>();
+				let index_expectation =
+					LitStr::new(&format!("field index 0 <= i < {}", len), Span::mixed_site());
+				let content = quote!(#serde_seeded::content::Content);
+				let (other_variant, other_index, other_str, other_bytes, other_arm) =
+					match unknown_fields {
+						// Unknown keys are buffered for the flattened fields and only handled once those are done.
+						_ if !flattened.is_empty() => (
+							quote_spanned!(Span::mixed_site()=> __other(#content<'de>),),
+							quote_spanned!(Span::mixed_site()=> Ok(__Field::__other(#content::U64(value)))),
+							quote_spanned!(Span::mixed_site()=> Ok(__Field::__other(#content::String(::std::borrow::ToOwned::to_owned(value))))),
+							quote_spanned!(Span::mixed_site()=> Ok(__Field::__other(#content::ByteBuf(::std::borrow::ToOwned::to_owned(value))))),
+							quote_spanned! {Span::mixed_site()=>
+								__Field::__other(key) => {
+									flat_entries.push(::std::option::Option::Some((key, map.next_value()?)));
+								}
+							},
+						),
+						UnknownFields::Ignore => (
+							quote_spanned!(Span::mixed_site()=> __ignore,),
+							quote_spanned!(Span::mixed_site()=> Ok(__Field::__ignore)),
+							quote_spanned!(Span::mixed_site()=> Ok(__Field::__ignore)),
+							quote_spanned!(Span::mixed_site()=> Ok(__Field::__ignore)),
+							quote_spanned! {Span::mixed_site()=>
+								__Field::__ignore => {
+									map.next_value::<de::IgnoredAny>()?;
+								}
+							},
+						),
+						UnknownFields::Deny => (
+							quote!(),
+							quote_spanned!(Span::mixed_site()=> Err(de::Error::invalid_value(de::Unexpected::Unsigned(value), &#index_expectation))),
+							quote_spanned!(Span::mixed_site()=> Err(de::Error::unknown_field(value, &FIELD_NAMES))),
+							quote_spanned!(Span::mixed_site()=> Err(de::Error::unknown_field(&::std::string::String::from_utf8_lossy(value), &FIELD_NAMES))),
+							quote!(),
+						),
+						UnknownFields::With(callback) => (
+							quote_spanned!(Span::mixed_site()=> __other(::std::string::String),),
+							quote_spanned!(Span::mixed_site()=> Ok(__Field::__other(::std::string::ToString::to_string(&value)))),
+							quote_spanned!(Span::mixed_site()=> Ok(__Field::__other(::std::borrow::ToOwned::to_owned(value)))),
+							quote_spanned!(Span::mixed_site()=> Ok(__Field::__other(::std::string::String::from_utf8_lossy(value).into_owned()))),
+							quote_spanned! {Span::mixed_site()=>
+								__Field::__other(key) => {
+									let value = map.next_value::<#serde_seeded::content::Content<#de>>()?;
+									(#callback)(&key, value);
+								}
+							},
+						),
+					};
+				// Keys of fields that aren't part of the input's version are unknown there, though under their canonical names.
+				let out_of_version = seeded
+					.iter()
+					.zip(&variants)
+					.filter_map(|(SeededField { name, versions, .. }, variant)| {
+						let condition = versions.as_ref()?.condition();
+						let handle = match unknown_fields {
+							_ if !flattened.is_empty() => quote_spanned! {Span::mixed_site()=>
+								flat_entries.push(::std::option::Option::Some((#content::Str(#name), map.next_value()?)));
+							},
+							UnknownFields::Ignore => quote_spanned! {Span::mixed_site()=>
+								map.next_value::<de::IgnoredAny>()?;
+							},
+							UnknownFields::Deny => quote_spanned! {Span::mixed_site()=>
+								return ::std::result::Result::Err(de::Error::unknown_field(#name, &FIELD_NAMES));
+							},
+							UnknownFields::With(callback) => quote_spanned! {Span::mixed_site()=>
+								let key = ::std::string::String::from(#name);
+								let value = map.next_value::<#content<#de>>()?;
+								(#callback)(&key, value);
+							},
+						};
+						Some(quote_spanned! {Span::mixed_site()=>
+							__Field::#variant if !#condition => {
+								#handle
+							}
+						})
+					})
+					.collect::<Vec<_>>();
+				let name_byte_patterns = seeded
+					.iter()
+					.map(|SeededField { name, aliases, .. }| {
+						let bytes = std::iter::once(name)
+							.chain(aliases)
+							.map(|name| LitByteStr::new(name.value().as_bytes(), name.span()));
+						quote!(#(#bytes)|*)
+					})
+					.collect::<Vec<_>>();
+
+				let (field_lifetime, visit_borrowed) = if flattened.is_empty() {
+					(quote!(), quote!())
+				} else {
+					(
+						quote!(<'de>),
+						// Unknown keys can be buffered without copying them.
+						quote_spanned! {Span::mixed_site()=>
+							fn visit_borrowed_str<E: de::Error>(self, value: &'de str) -> ::std::result::Result<Self::Value, E> {
+								match value {
+									#(#name_patterns => Ok(__Field::#variants),)*
+									_ => Ok(__Field::__other(#content::Str(value))),
+								}
+							}
+
+							fn visit_borrowed_bytes<E: de::Error>(self, value: &'de [u8]) -> ::std::result::Result<Self::Value, E> {
+								match value {
+									#(#name_byte_patterns => Ok(__Field::#variants),)*
+									_ => Ok(__Field::__other(#content::Bytes(value))),
+								}
+							}
+						},
+					)
+				};
+
+				let flat_locals = flattened.iter().map(|f| &f.local).collect::<Vec<_>>();
+				let flat_seeds = flattened.iter().map(|f| &f.seed).collect::<Vec<_>>();
+				let (flat_entries, read_flattened) = if flattened.is_empty() {
+					(quote!(), quote!())
+				} else {
+					let leftovers = match unknown_fields {
+						UnknownFields::Ignore => quote!(),
+						UnknownFields::Deny => quote_spanned! {Span::mixed_site()=>
+							if let ::std::option::Option::Some((key, _)) = flat_entries.iter().flatten().next() {
+								return ::std::result::Result::Err(de::Error::unknown_field(key.as_str().unwrap_or("(not a string)"), &FIELD_NAMES));
+							}
+						},
+						UnknownFields::With(callback) => quote_spanned! {Span::mixed_site()=>
+							for (key, value) in flat_entries.into_iter().flatten() {
+								if let ::std::option::Option::Some(key) = key.as_str().map(::std::borrow::ToOwned::to_owned) {
+									(#callback)(&key, value);
+								}
+							}
+						},
+					};
+					(
+						quote_spanned! {Span::mixed_site()=>
+							let mut flat_entries: #serde_seeded::__private::FlatEntries<#de> = ::std::vec::Vec::new();
+						},
+						quote_spanned! {Span::mixed_site()=>
+							#(let #flat_locals = de::DeserializeSeed::deserialize(
+								#flat_seeds,
+								#serde_seeded::__private::FlatMapDeserializer::<__A::Error>::new(&mut flat_entries),
+							)?;)*
+							#leftovers
+						},
+					)
+				};
+
+				(
+					quote_spanned! {Span::mixed_site()=>
+						const FIELD_NAMES: [&'static str; #len] = [#(#names, )*];
+
+						#[allow(non_camel_case_types)]
+						enum __Field #field_lifetime {
+							#(#variants,)*
+							#other_variant
+						}
+						impl<'de> de::Deserialize<'de> for __Field #field_lifetime {
+							fn deserialize<__D: de::Deserializer<'de>>(deserializer: __D) -> ::std::result::Result<Self, __D::Error> {
+								struct FieldVisitor<'de>(::std::marker::PhantomData<&'de ()>);
+								impl<'de> de::Visitor<'de> for FieldVisitor<'de> {
+									type Value = __Field #field_lifetime;
+									fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
+										f.write_str("field identifier")
+									}
+
+									fn visit_u64<E: de::Error>(self, value: u64) -> ::std::result::Result<Self::Value, E> {
+										match value {
+											#(#indices => Ok(__Field::#variants),)*
+											_ => #other_index,
+										}
+									}
+
+									fn visit_str<E: de::Error>(self, value: &str) -> ::std::result::Result<Self::Value, E> {
+										match value {
+											#(#name_patterns => Ok(__Field::#variants),)*
+											_ => #other_str,
+										}
+									}
+
+									fn visit_bytes<E: de::Error>(self, value: &[u8]) -> ::std::result::Result<Self::Value, E> {
+										match value {
+											#(#name_byte_patterns => Ok(__Field::#variants),)*
+											_ => #other_bytes,
+										}
+									}
+
+									#visit_borrowed
+								}
+								deserializer.deserialize_identifier(FieldVisitor(::std::marker::PhantomData))
+							}
+						}
+					},
+					quote_spanned! {Span::mixed_site()=>
+						fn visit_map<__A: de::MapAccess<#de>>(self, mut map: __A) -> ::std::result::Result<Self::Value, __A::Error> {
+							#take_args
+
+							#serde_seeded::log::trace!("Starting to deserialize {} from a map...", #label);
+							#(let mut #locals = ::std::option::Option::None;)*
+							#(let mut #buffers = ::std::option::Option::None;)*
+							#flat_entries
+							{
+								#(let mut #slots = ::std::option::Option::Some(#early_seeds);)*
+								while let ::std::option::Option::Some(key) = map.next_key::<__Field>()? {
+									match key {
+										#(#out_of_version)*
+										#(__Field::#variants => {
+											#read_values
+										})*
+										#other_arm
+									}
+								}
+							}
+							// Like the other seeders, those of flattened fields run before any field is bound under its name.
+							#read_flattened
+							#(
+								#replay_buffered
+								#unwrap_values
+							)*
+							#(#skipped)*
+							#(#validations)*
+							#serde_seeded::log::trace!("Done deserializing {}.", #label);
+
+							#construct
+						}
+					},
+				)
+			}
+			Fields::Unnamed(_) | Fields::Unit => (quote!(), quote!()),
+		};
+
+		let visit_other = match fields {
+			Fields::Unnamed(_) if len == 1 => quote_spanned! {Span::mixed_site()=>
+				fn visit_newtype_struct<__D: de::Deserializer<#de>>(self, deserializer: __D) -> ::std::result::Result<Self::Value, __D::Error> {
+					#take_args
+
+					#serde_seeded::log::trace!("Starting to deserialize {} from a newtype...", #label);
+					#(let #locals = de::DeserializeSeed::deserialize(#seeds, deserializer)?;)*
+					#(#skipped)*
+					#(#validations)*
+					#serde_seeded::log::trace!("Done deserializing {}.", #label);
+
+					#construct
+				}
+			},
+			Fields::Unit => quote_spanned! {Span::mixed_site()=>
+				fn visit_unit<__E: de::Error>(self) -> ::std::result::Result<Self::Value, __E> {
+					#take_args
+					#construct
 				}
+			},
+			Fields::Named(_) | Fields::Unnamed(_) => quote!(),
+		};
 
-				errors.extend(
-					field.attrs.iter()
-						.filter(|a| a.path.is_ident("seed_args"))
-						.map(|a| Error::new_spanned(a, "Misplaced #[seeded_args]: This attribute is only valid on the type's definition")
-							.to_compile_error()
-						)
-					);
+		let expecting = match &versioned_len {
+			Some(count) => quote_spanned! {Span::mixed_site()=>
+				let version = self.__version;
+				let len: usize = #count;
+				write!(f, "{} with {} element{}", #prefix, len, if len == 1 { "" } else { "s" })
+			},
+			None => quote_spanned!(Span::mixed_site()=> f.write_str(#expecting)),
+		};
+		let visitor = visitor(quote_spanned! {Span::mixed_site()=>
+			fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
+				#expecting
+			}
 
-				let attr = attrs.drain(..).next();
-				assert_eq!(attrs.into_iter().count(), 0);
+			#visit_seq
+			#visit_map
+			#visit_other
+		});
 
-				nexts.push(if let Some(attr) = attr {
-					if attr.tokens.is_empty() {
-						let ty = &field.ty;
-						quote_spanned!(ty.span()=> next_element_seed(#ty::seed()))
-					} else {
-						let tokens = &attr.tokens;
+		quote! {
+			#field_identifier
+			#visitor
+		}
+	};
 
-						call2(quote!(#tokens), |tokens| {
-							let content;
-							let paren = parenthesized!(content in tokens);
-							Ok((paren, content.parse()?))
+	let deserialize = match &input.data {
+		Data::Struct(data) => {
+			let seeded = seeded_fields(&data.fields, &shared.fields[0], rename_all, profile, &args);
+			let container = match &data.fields {
+				Fields::Named(_) => "struct",
+				Fields::Unnamed(_) => "tuple struct",
+				Fields::Unit => "unit struct",
+			};
+			let visitor = fields_visitor(
+				container,
+				&LitStr::new(&name.to_string(), name.span()),
+				quote!(#name),
+				&data.fields,
+				&seeded,
+				&complete,
+			);
+			let deserialize = match &data.fields {
+				Fields::Named(_) if seeded.iter().any(|f| f.skip.is_none() && f.flatten) => {
+					quote_spanned! {Span::mixed_site()=>
+						deserializer.deserialize_map(Visitor {
+							#give_args
 						})
-						.map_or_else(
-							|error| error.to_compile_error(),
-							|(paren, custom_seeder): (_, TokenStream)| {
-									quote_spanned!(paren.span=> next_element_seed(#custom_seeder.seed()))
+					}
+				}
+				Fields::Named(_) => {
+					let field_names = field_names(&seeded, version.as_ref());
+					quote_spanned! {Span::mixed_site()=>
+					deserializer.deserialize_struct(
+						stringify!(#name),
+						#field_names,
+						Visitor {
+							#give_args
+						},
+					)
+					}
+				}
+				Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+					quote_spanned! {Span::mixed_site()=>
+						deserializer.deserialize_newtype_struct(
+							stringify!(#name),
+							Visitor {
+								#give_args
 							},
 						)
 					}
-				} else {
-					errors.push(Error::new_spanned(field, "#[seeded] or #[seeded_de] required").to_compile_error());
-					let ty = &field.ty;
-					quote_spanned!(ty.span()=> next_element_seed(#ty::seed()))
-				});
+				}
+				Fields::Unnamed(_) => {
+					let len = element_count(&seeded);
+					quote_spanned! {Span::mixed_site()=>
+						deserializer.deserialize_tuple_struct(
+							stringify!(#name),
+							#len,
+							Visitor {
+								#give_args
+							},
+						)
+					}
+				}
+				Fields::Unit => quote_spanned! {Span::mixed_site()=>
+					deserializer.deserialize_unit_struct(
+						stringify!(#name),
+						Visitor {
+							#give_args
+						},
+					)
+				},
+			};
+
+			quote_spanned! {Span::mixed_site()=>
+				#visitor
+
+				#take_args
+				#deserialize
 			}
-			let len = field_idents.len();
+		}
+		Data::Enum(data) => {
+			let tagging = &shared.tagging;
+			// Untagged variants are only attempted, so the value is completed once one of them matches.
+			let complete_variant = |construct: TokenStream| match tagging {
+				Tagging::Untagged => {
+					quote_spanned!(Span::mixed_site()=> ::std::result::Result::Ok(#construct))
+				}
+				_ => complete(construct),
+			};
 
-			Ok(quote_spanned! {Span::mixed_site()=>
-				#(#errors)*
-				#[automatically_derived]
-				impl<
-					#(#type_generics_types)*
-				> #name<
-					#(#type_generics_type_idents,)*
-				> #type_generics_where {
-					pub fn seed<
-						#(#default_de,)*
-						#(#fn_generics_lifetimes,)*
-						#(#fn_generics_types,)*
-					>(#(#args),*) -> impl #serde_seeded::serde::de::DeserializeSeed<#de, Value = Self> {
-
-						//TODO: Use fully qualified calls instead.
-						use #serde_seeded::{
-							DeSeeder as _,
-							SerSeeder as _,
-							serde::de,
-						};
+			let len = data.variants.len();
+			let variants = (0..len)
+				.map(|i| Ident::new(&format!("__variant{}", i), Span::mixed_site()))
+				.collect::<Vec<_>>();
+			let variant_idents = data.variants.iter().map(|v| &v.ident).collect::<Vec<_>>();
+			let variant_names = variant_idents
+				.iter()
+				.map(|ident| case::variant_name(ident, rename_all))
+				.collect::<Vec<_>>();
 
-						struct Seed<
-							#(#fn_generics_lifetimes,)*
-							#(#type_generics_types,)*
-							#(#fn_generics_types,)*
-						> {
-							#(#args,)*
-
-							// Note: This is technically avoidable, I think, but that decoupling wouldn't
-							// lead to additional compatibility of the outer method and seems like it would
-							// make the macro harder to reason about.
-							limiter: ::std::marker::PhantomData<(
-								#(&#fn_generics_lifetime_lifetimes (),)*
-								#(#type_generics_types,)*
-								// But not #fn_generics_types, since those should appear in the #args.
-							)>,
-						};
-						impl<
-							#(#default_de,)*
-							#(#fn_generics_lifetimes,)*
-							#(#type_generics_types,)*
-							#(#fn_generics_types,)*
-							> de::DeserializeSeed<#de> for Seed<
-								#(#fn_generics_lifetime_lifetimes,)*
-								#(#type_generics_type_idents,)*
-								#(#fn_generics_type_idents,)*
-							> #type_generics_where {
-							type Value = #name<#(#type_generics_type_idents)*>;
-							fn deserialize<D: de::Deserializer<#de>>(self, deserializer: D) -> ::std::result::Result<Self::Value, D::Error> {
-								struct Visitor<
-									#(#fn_generics_lifetimes,)*
-									#(#type_generics_types,)*
-									#(#fn_generics_types,)*
-								> {
-									#(#args,)*
-									limiter: ::std::marker::PhantomData<(
-										#(&#fn_generics_lifetime_lifetimes (),)*
-										#(#type_generics_types,)*
-									)>,
-								};
-								impl<
-									#(#default_de,)*
-									#(#fn_generics_lifetimes,)*
-									#(#type_generics_types,)*
-									#(#fn_generics_types,)*
-								> de::Visitor<#de> for Visitor<
-									#(#fn_generics_lifetime_lifetimes,)*
-									#(#type_generics_type_idents,)*
-									#(#fn_generics_type_idents,)*
-								> #type_generics_where {
-									type Value = #name<
-										#(#type_generics_type_idents,)*
-									>;
-									fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
-										write!(f, stringify!(#name))
-									}
+			// External tagging reads each variant through `VariantAccess`, the other representations
+			// hand its content to a `VariantSeed` as a plain `Deserializer`.
+			let mut access_arms = vec![];
+			let mut content_arms = vec![];
+			for ((variant, variant_name), attrs) in data
+				.variants
+				.iter()
+				.zip(variant_names.iter())
+				.zip(shared.fields.iter())
+			{
+				let variant_ident = &variant.ident;
+				let label = LitStr::new(
+					&format!("{}::{}", name, variant_ident),
+					variant_ident.span(),
+				);
 
-									fn visit_seq<A: de::SeqAccess<#de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
-										let Self {
-											#(#arg_names,)*
-											limiter: _,
-										} = self;
+				let seeded = seeded_fields(&variant.fields, attrs, None, profile, &args);
+				let path = quote!(#name::#variant_ident);
+				match &variant.fields {
+					Fields::Unit => {
+						let construct = complete_variant(path);
+						access_arms.push(quote_spanned! {Span::mixed_site()=>
+							de::VariantAccess::unit_variant(variant)?;
+							#construct
+						});
+						content_arms.push(if let Tagging::Internal { .. } = tagging {
+							// The content is whatever is left of the map besides the tag.
+							quote_spanned! {Span::mixed_site()=>
+								de::Deserializer::deserialize_ignored_any(deserializer, de::IgnoredAny)?;
+								#construct
+							}
+						} else {
+							quote_spanned! {Span::mixed_site()=>
+								<() as de::Deserialize>::deserialize(deserializer)?;
+								#construct
+							}
+						});
+					}
+					Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+						let SeededField {
+							local,
+							seed,
+							validate,
+							..
+						} = &seeded[0];
+						let validation = validate
+							.as_ref()
+							.map(|validate| field_validation(validate, local));
+						let construct = complete_variant(quote!(#path(#local)));
+						access_arms.push(quote_spanned! {Span::mixed_site()=>
+							let #local = de::VariantAccess::newtype_variant_seed(variant, #seed)?;
+							#validation
+							#construct
+						});
+						content_arms.push(quote_spanned! {Span::mixed_site()=>
+							let #local = de::DeserializeSeed::deserialize(#seed, deserializer)?;
+							#validation
+							#construct
+						});
+					}
+					Fields::Unnamed(_) => {
+						let len = element_count(&seeded);
+						let visitor = fields_visitor(
+							"tuple variant",
+							&label,
+							path,
+							&variant.fields,
+							&seeded,
+							&complete_variant,
+						);
+						access_arms.push(quote_spanned! {Span::mixed_site()=>
+							#visitor
+							de::VariantAccess::tuple_variant(variant, #len, Visitor {
+								#give_args
+							})
+						});
+						content_arms.push(quote_spanned! {Span::mixed_site()=>
+							#visitor
+							de::Deserializer::deserialize_tuple(deserializer, #len, Visitor {
+								#give_args
+							})
+						});
+					}
+					Fields::Named(_) => {
+						let field_names = field_names(&seeded, version.as_ref());
+						let visitor = fields_visitor(
+							"struct variant",
+							&label,
+							path,
+							&variant.fields,
+							&seeded,
+							&complete_variant,
+						);
+						access_arms.push(quote_spanned! {Span::mixed_site()=>
+							#visitor
+							de::VariantAccess::struct_variant(variant, #field_names, Visitor {
+								#give_args
+							})
+						});
+						content_arms.push(quote_spanned! {Span::mixed_site()=>
+							#visitor
+							de::Deserializer::deserialize_struct(deserializer, #variant_name, #field_names, Visitor {
+								#give_args
+							})
+						});
+					}
+				}
+			}
 
-										#serde_seeded::log::trace!("Starting to deserialize {}...", stringify!(#name));
-										#(let #field_idents = seq.#nexts?.ok_or_else(|| de::Error::invalid_length(0, &format!("Struct with {} fields", #len).as_str()))?;)*
-										#serde_seeded::log::trace!("Done deserializing {}.", stringify!(#name));
+			let indices = (0..len as u64).collect::<Vec<_>>();
+			let variant_name_bytes = variant_names
+				.iter()
+				.map(|name| LitByteStr::new(name.value().as_bytes(), name.span()))
+				.collect::<Vec<_>>();
+			let index_expectation = LitStr::new(
+				&format!("variant index 0 <= i < {}", len),
+				Span::mixed_site(),
+			);
+
+			let variant_identifier = quote_spanned! {Span::mixed_site()=>
+				const VARIANTS: [&'static str; #len] = [#(#variant_names, )*];
+
+				impl<'de> de::Deserialize<'de> for __Variant {
+					fn deserialize<__D: de::Deserializer<'de>>(deserializer: __D) -> ::std::result::Result<Self, __D::Error> {
+						struct VariantVisitor;
+						impl<'de> de::Visitor<'de> for VariantVisitor {
+							type Value = __Variant;
+							fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
+								f.write_str("variant identifier")
+							}
 
-										Ok(#name {
-											#(#field_idents,)*
-										})
+							fn visit_u64<E: de::Error>(self, value: u64) -> ::std::result::Result<Self::Value, E> {
+								match value {
+									#(#indices => Ok(__Variant::#variants),)*
+									_ => Err(de::Error::invalid_value(de::Unexpected::Unsigned(value), &#index_expectation)),
+								}
+							}
+
+							fn visit_str<E: de::Error>(self, value: &str) -> ::std::result::Result<Self::Value, E> {
+								match value {
+									#(#variant_names => Ok(__Variant::#variants),)*
+									_ => Err(de::Error::unknown_variant(value, &VARIANTS)),
+								}
+							}
+
+							fn visit_bytes<E: de::Error>(self, value: &[u8]) -> ::std::result::Result<Self::Value, E> {
+								match value {
+									#(#variant_name_bytes => Ok(__Variant::#variants),)*
+									_ => Err(de::Error::unknown_variant(&String::from_utf8_lossy(value), &VARIANTS)),
+								}
+							}
+						}
+						deserializer.deserialize_identifier(VariantVisitor)
+					}
+				}
+			};
+
+			let variant_seed = carrier(
+				quote!(VariantSeed),
+				quote_spanned!(Span::mixed_site()=> variant: __Variant,),
+				quote_spanned!(Span::mixed_site()=> de::DeserializeSeed<#de>),
+				quote_spanned! {Span::mixed_site()=>
+					type Value = #self_ty;
+					fn deserialize<__D: de::Deserializer<#de>>(self, deserializer: __D) -> ::std::result::Result<Self::Value, __D::Error> {
+						let Self {
+							#(#arg_names,)*
+							#version_binding
+							variant,
+							limiter: _,
+						} = self;
+						#(let _ = &#arg_idents;)*
+
+						match variant {
+							#(__Variant::#variants => {
+								#serde_seeded::log::trace!("Deserializing variant {}::{}...", stringify!(#name), #variant_names);
+								#content_arms
+							})*
+						}
+					}
+				},
+			);
+
+			let content_ref_deserializer = quote!(#serde_seeded::content::ContentRefDeserializer);
+			let body = match &tagging {
+				Tagging::External => {
+					let label = LitStr::new(&format!("enum {}", name), name.span());
+					let visitor = visitor(quote_spanned! {Span::mixed_site()=>
+						fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
+							f.write_str(#label)
+						}
+
+						fn visit_enum<__A: de::EnumAccess<#de>>(self, data: __A) -> ::std::result::Result<Self::Value, __A::Error> {
+							#take_args
+
+							match de::EnumAccess::variant::<__Variant>(data)? {
+								#((__Variant::#variants, variant) => {
+									#serde_seeded::log::trace!("Deserializing variant {}::{}...", stringify!(#name), #variant_names);
+									#access_arms
+								})*
+							}
+						}
+					});
+
+					quote_spanned! {Span::mixed_site()=>
+						#variant_identifier
+						#visitor
+
+						#take_args
+						deserializer.deserialize_enum(
+							stringify!(#name),
+							VARIANTS.as_ref(),
+							Visitor {
+								#give_args
+							},
+						)
+					}
+				}
+				Tagging::Internal { tag } => {
+					let label =
+						LitStr::new(&format!("internally tagged enum {}", name), name.span());
+					quote_spanned! {Span::mixed_site()=>
+						#variant_identifier
+						#variant_seed
+
+						#take_args
+						let (variant, content) = #serde_seeded::__private::deserialize_internally_tagged::<__Variant, __D>(deserializer, #tag, #label)?;
+						de::DeserializeSeed::deserialize(
+							VariantSeed {
+								variant,
+								#give_args
+							},
+							#content_ref_deserializer::<__D::Error>::new(&content),
+						)
+					}
+				}
+				Tagging::Adjacent { tag, content } => {
+					let label =
+						LitStr::new(&format!("adjacently tagged enum {}", name), name.span());
+					let key_seed = quote_spanned! {Span::mixed_site()=>
+						#serde_seeded::__private::AdjacentKeySeed {
+							tag: #tag,
+							content: #content,
+						}
+					};
+					let adjacent_key = quote!(#serde_seeded::__private::AdjacentKey);
+					let visitor = visitor(quote_spanned! {Span::mixed_site()=>
+						fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
+							f.write_str(#label)
+						}
+
+						fn visit_seq<__A: de::SeqAccess<#de>>(self, mut seq: __A) -> ::std::result::Result<Self::Value, __A::Error> {
+							#take_args
+
+							let variant = seq.next_element::<__Variant>()?.ok_or_else(|| de::Error::invalid_length(0, &#label))?;
+							seq.next_element_seed(VariantSeed {
+								variant,
+								#give_args
+							})?.ok_or_else(|| de::Error::invalid_length(1, &#label))
+						}
+
+						fn visit_map<__A: de::MapAccess<#de>>(self, mut map: __A) -> ::std::result::Result<Self::Value, __A::Error> {
+							#take_args
+
+							let mut variant = ::std::option::Option::None;
+							let mut content = ::std::option::Option::None;
+							while let ::std::option::Option::Some(key) = map.next_key_seed(#key_seed)? {
+								match key {
+									#adjacent_key::Tag => {
+										if ::std::option::Option::is_some(&variant) {
+											return ::std::result::Result::Err(de::Error::duplicate_field(#tag));
+										}
+										variant = ::std::option::Option::Some(map.next_value::<__Variant>()?);
+									}
+									#adjacent_key::Content => {
+										if ::std::option::Option::is_some(&content) {
+											return ::std::result::Result::Err(de::Error::duplicate_field(#content));
+										}
+										match variant {
+											// The tag is known already, so the content doesn't need to be buffered.
+											::std::option::Option::Some(variant) => {
+												let value = map.next_value_seed(VariantSeed {
+													variant,
+													#give_args
+												})?;
+												while let ::std::option::Option::Some(key) = map.next_key_seed(#key_seed)? {
+													match key {
+														#adjacent_key::Tag => return ::std::result::Result::Err(de::Error::duplicate_field(#tag)),
+														#adjacent_key::Content => return ::std::result::Result::Err(de::Error::duplicate_field(#content)),
+														#adjacent_key::Other => {
+															map.next_value::<de::IgnoredAny>()?;
+														}
+													}
+												}
+												return ::std::result::Result::Ok(value);
+											}
+											::std::option::Option::None => {
+												content = ::std::option::Option::Some(map.next_value::<#serde_seeded::content::Content>()?);
+											}
+										}
+									}
+									#adjacent_key::Other => {
+										map.next_value::<de::IgnoredAny>()?;
 									}
 								}
+							}
 
-								let Self {
-									#(#arg_names,)*
-									limiter: _,
-								} = self;
-								const FIELD_NAMES: [&'static str; #len] = [#(stringify!(#field_idents), )*];
-								deserializer.deserialize_struct(
-									stringify!(#name),
-									FIELD_NAMES.as_ref(),
-									Visitor {
-										#(#arg_names,)*
-										limiter: ::std::marker::PhantomData,
+							match (variant, content) {
+								(::std::option::Option::Some(variant), ::std::option::Option::Some(content)) => de::DeserializeSeed::deserialize(
+									VariantSeed {
+										variant,
+										#give_args
+									},
+									#content_ref_deserializer::<__A::Error>::new(&content),
+								),
+								// Only unit variants can omit their content.
+								(::std::option::Option::Some(variant), ::std::option::Option::None) => de::DeserializeSeed::deserialize(
+									VariantSeed {
+										variant,
+										#give_args
 									},
-								)
+									de::IntoDeserializer::<__A::Error>::into_deserializer(()),
+								),
+								(::std::option::Option::None, _) => ::std::result::Result::Err(de::Error::missing_field(#tag)),
 							}
 						}
+					});
 
-						Seed {
-							#(#arg_names,)*
-							limiter: ::std::marker::PhantomData,
+					quote_spanned! {Span::mixed_site()=>
+						#variant_identifier
+						#variant_seed
+						#visitor
+
+						#take_args
+						deserializer.deserialize_struct(
+							stringify!(#name),
+							&[#tag, #content],
+							Visitor {
+								#give_args
+							},
+						)
+					}
+				}
+				Tagging::Untagged => {
+					let message = LitStr::new(
+						&format!("data did not match any variant of untagged enum {}", name),
+						name.span(),
+					);
+					// Like `share_args`, but pointing at an owned argument's type if it can't be cloned.
+					let untagged_args = args.iter().filter_map(|arg| match arg {
+						FnArg::Receiver(_) => None,
+						FnArg::Typed(PatType { pat, ty, .. }) if is_mutable_reference(ty) => {
+							Some(quote_spanned!(Span::mixed_site()=> &mut *#pat))
 						}
+						FnArg::Typed(PatType { pat, ty, .. }) => Some(quote_spanned! {ty.span()=>
+							#serde_seeded::__private::clone_for_untagged_variant(&#pat)
+						}),
+					});
+					let construct = complete(quote_spanned!(Span::mixed_site()=> value));
+					let untagged_share_args = quote_spanned! {Span::mixed_site()=>
+						#(#arg_names: #untagged_args,)*
+						#version_binding
+						limiter: ::std::marker::PhantomData,
+					};
+					quote_spanned! {Span::mixed_site()=>
+						#variant_seed
+
+						let content = <#serde_seeded::content::Content as de::Deserialize>::deserialize(deserializer)?;
+						#take_args
+						#(
+							if let ::std::result::Result::Ok(value) = de::DeserializeSeed::deserialize(
+								VariantSeed {
+									variant: __Variant::#variants,
+									#untagged_share_args
+								},
+								#content_ref_deserializer::<__D::Error>::new(&content),
+							) {
+								return #construct;
+							}
+						)*
+						::std::result::Result::Err(de::Error::custom(#message))
 					}
 				}
-			})
+			};
+
+			quote_spanned! {Span::mixed_site()=>
+				#[allow(non_camel_case_types)]
+				enum __Variant {
+					#(#variants,)*
+				}
+
+				#body
+			}
 		}
-		Data::Enum(e) => Err(Error::new_spanned(e.enum_token, "serde-seeded derive macros are not available on enums yet. You can request this feature at <https://github.com/Tamschi/serde-seeded/issues/5>.")),
-		Data::Union(u) => Err(Error::new_spanned(u.union_token, "serde-seeded derive macros are not available on unions yet. You can request this feature at <https://github.com/Tamschi/serde-seeded/issues/6>.")),
+		// Rejected by `parse_shared`.
+		Data::Union(_) => unreachable!(),
+	};
+
+	// A conversion replaces reading the fields.
+	let deserialize = match &shared.conversion {
+		None => deserialize,
+		Some(Conversion {
+			raw,
+			with,
+			fallible,
+			seeder,
+		}) => {
+			let seed = seed_expr(seeder, raw, &args);
+			let convert = if *fallible {
+				quote_spanned!(Span::mixed_site()=> #with(raw, #(#arg_names),*).map_err(de::Error::custom))
+			} else {
+				quote_spanned!(Span::mixed_site()=> ::std::result::Result::Ok(#with(raw, #(#arg_names),*)))
+			};
+			let construct = complete(quote_spanned!(Span::mixed_site()=> value));
+			quote_spanned! {Span::mixed_site()=>
+				#take_args
+				let raw = de::DeserializeSeed::deserialize(#seed, deserializer)?;
+				let value = #convert?;
+				#construct
+			}
+		}
+	};
+
+	// The version of the input is read or computed first and then carried to the visitors by an inner seed.
+	let deserialize = match version {
+		None => deserialize,
+		Some(Version {
+			current, source, ..
+		}) => {
+			let inner = carrier(
+				quote!(Inner),
+				quote!(),
+				quote_spanned!(Span::mixed_site()=> de::DeserializeSeed<#de>),
+				quote_spanned! {Span::mixed_site()=>
+					type Value = #self_ty;
+					fn deserialize<__D: de::Deserializer<#de>>(self, deserializer: __D) -> ::std::result::Result<Self::Value, __D::Error> {
+						#deserialize
+					}
+				},
+			);
+			let read = match source {
+				VersionSource::Header => quote_spanned! {Span::mixed_site()=>
+					let (_, value) = de::DeserializeSeed::deserialize(
+						#serde_seeded::__private::VersionedSeed::new(#current, move |version| Inner {
+							#give_args
+						}),
+						deserializer,
+					)?;
+					::std::result::Result::Ok(value)
+				},
+				VersionSource::Arg(expr) => quote_spanned! {Span::mixed_site()=>
+					let version = #serde_seeded::__private::check_version::<__D::Error>(
+						::std::convert::Into::<u32>::into(#expr),
+						#current,
+					)?;
+					de::DeserializeSeed::deserialize(
+						Inner {
+							#give_args
+						},
+						deserializer,
+					)
+				},
+			};
+			quote_spanned! {Span::mixed_site()=>
+				#inner
+
+				#seed_take_args
+				#read
+			}
+		}
+	};
+
+	let arg_types = args
+		.iter()
+		.filter_map(|arg| match arg {
+			FnArg::Receiver(_) => None,
+			FnArg::Typed(PatType { ty, .. }) => Some(ty),
+		})
+		.collect::<Vec<_>>();
+
+	let type_where_predicates = type_generics_where
+		.iter()
+		.flat_map(|w| w.predicates.iter())
+		.collect::<Vec<_>>();
+
+	let (fn_vis, mut fn_ident) = shared
+		.seed_fn
+		.clone()
+		.unwrap_or_else(|| (parse_quote!(pub), Ident::new("seed", Span::call_site())));
+	if let Some(profile) = profile {
+		fn_ident = Ident::new(
+			&format!("{}_{}", fn_ident, profile.name),
+			profile.name.span(),
+		);
+	}
+
+	// A named seed type is declared next to the input. Otherwise, it's hidden in an anonymous constant,
+	// but as visible as the input so that it can appear in the `SeedWith` implementation.
+	let named = match profile {
+		None => shared.seed_type.as_ref(),
+		Some(_) => None,
+	};
+	let (seed_vis, seed_ident) = match &named {
+		Some((vis, ident)) => (vis.to_token_stream(), ident.clone()),
+		None => (
+			input.vis.to_token_stream(),
+			Ident::new("Seed", Span::mixed_site()),
+		),
+	};
+	let seed_ty = quote! {
+		#seed_ident<
+			#(#fn_generics_lifetime_lifetimes,)*
+			#(#type_generics_lifetime_lifetimes,)*
+			#(#type_generics_param_idents,)*
+			#(#fn_generics_param_idents,)*
+		>
+	};
+	let seed_generics = quote! {
+		#(#fn_generics_lifetimes,)*
+		#(#type_generics_lifetimes,)*
+		#(#type_generics_params,)*
+		#(#fn_generics_params,)*
+	};
+	let mut seed_item = quote_spanned! {Span::mixed_site()=>
+		#seed_vis struct #seed_ident<#seed_generics> #type_generics_where {
+			#(#args,)*
+
+			// Note: This is technically avoidable, I think, but that decoupling wouldn't
+			// lead to additional compatibility of the outer method and seems like it would
+			// make the macro harder to reason about.
+			limiter: ::std::marker::PhantomData<(
+				#(&#fn_generics_lifetime_lifetimes (),)*
+				#(&#type_generics_lifetime_lifetimes (),)*
+				#(#type_generics_type_idents,)*
+				// But not #fn_generics_params, since those should appear in the #args.
+			)>,
+		}
+		impl<
+			#(#default_de,)*
+			#seed_generics
+		> #serde_seeded::serde::de::DeserializeSeed<#de> for #seed_ty #seed_where {
+			type Value = #self_ty;
+			fn deserialize<__D: #serde_seeded::serde::de::Deserializer<#de>>(self, deserializer: __D) -> ::std::result::Result<Self::Value, __D::Error> {
+				//TODO: Use fully qualified calls instead.
+				use #serde_seeded::{
+					DeSeeder as _,
+					SerSeeder as _,
+					serde::de,
+				};
+
+				#deserialize
+			}
+		}
+	};
+	let seed_return = if named.is_some() {
+		let doc = format!("The seed returned by [`{}::{}`].", name, fn_ident);
+		let clone_where = clone_where(&args, &type_where_predicates);
+		seed_item = quote_spanned! {Span::mixed_site()=>
+			#[doc = #doc]
+			#seed_item
+
+			#[automatically_derived]
+			impl<#seed_generics> ::std::clone::Clone for #seed_ty #clone_where {
+				fn clone(&self) -> Self {
+					let Self {
+						#(#arg_names,)*
+						limiter: _,
+					} = self;
+					Self {
+						#(#arg_names: ::std::clone::Clone::clone(#arg_names),)*
+						limiter: ::std::marker::PhantomData,
+					}
+				}
+			}
+
+			#[automatically_derived]
+			impl<#seed_generics> ::std::fmt::Debug for #seed_ty #type_generics_where {
+				fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+					f.debug_struct(stringify!(#seed_ident)).finish_non_exhaustive()
+				}
+			}
+		};
+		seed_ty.clone()
+	} else {
+		quote! {
+			impl #serde_seeded::serde::de::DeserializeSeed<#de, Value = Self>
+				#(+ #serde_seeded::__private::Captures<#fn_generics_lifetime_lifetimes>)*
+		}
+	};
+
+	// Profiles that share their argument types with another would implement the trait twice, so they don't.
+	let distinct_args = !matches!(profile, Some(profile) if !profile.distinct_args);
+	let seed_with = distinct_args.then(|| {
+		quote_spanned! {Span::mixed_site()=>
+			#[automatically_derived]
+			impl<
+				#(#default_de,)*
+				#seed_generics
+			> #serde_seeded::SeedWith<#de, (#(#arg_types,)*)> for #self_ty #seed_where {
+				type Seed = #seed_ty;
+				fn seed_with((#(#arg_names,)*): (#(#arg_types,)*)) -> Self::Seed {
+					#seed_ident {
+						#seed_give_args
+					}
+				}
+			}
+		}
+	});
+
+	let items = quote_spanned! {Span::mixed_site()=>
+		#seed_item
+		#seed_with
+
+		#[automatically_derived]
+		impl<
+			#(#type_generics_lifetimes,)*
+			#(#type_generics_params,)*
+		> #self_ty #type_generics_where {
+			#fn_vis fn #fn_ident<
+				#(#default_de,)*
+				#(#fn_generics_lifetimes,)*
+				#(#fn_generics_params,)*
+			>(#(#args),*) -> #seed_return
+			where
+				#(#de_outlives,)*
+				#(#fn_where,)*
+			{
+				#seed_ident {
+					#seed_give_args
+				}
+			}
+		}
+	};
+
+	if named.is_some() {
+		items
+	} else {
+		quote! {
+			const _: () = {
+				#items
+			};
+		}
+	}
+}
+
+struct SeededField<'a> {
+	/// The field's identifier, if it's named.
+	ident: Option<&'a Ident>,
+	/// The hygienic local variable the field is deserialized into,
+	/// so that the seed arguments aren't shadowed by fields of the same name.
+	local: Ident,
+	/// The field's name as passed to the [`Deserializer`](serde::Deserializer).
+	name: LitStr,
+	/// Other names the field is accepted under.
+	aliases: Vec<LitStr>,
+	/// An expression evaluating to the field's [`DeserializeSeed`](serde::de::DeserializeSeed).
+	seed: TokenStream,
+	/// An expression evaluating to the field's value if it's missing from the input.
+	default: Option<TokenStream>,
+	/// An expression evaluating to the field's value if it's never read from the input.
+	skip: Option<TokenStream>,
+	/// Whether the field is read from the entries its container doesn't recognise.
+	flatten: bool,
+	/// The format versions the field appears in, if limited.
+	versions: Option<FieldVersions>,
+	/// A function that checks the field's value.
+	validate: Option<Expr>,
+	/// The identifiers of earlier fields that the seeder refers to.
+	dependencies: Vec<&'a Ident>,
+	/// Whether the seed can be built before the map is read, since it needs neither earlier fields
+	/// nor reborrows of mutable reference arguments, so that it's built once even where it consumes an argument.
+	early: bool,
+}
+
+/// The names of the fields that appear in the input, for `deserialize_struct` and `struct_variant`.
+///
+/// With fields limited to some format versions, these depend on the local `version`,
+/// which formats like bincode need to know how many fields to read.
+fn field_names(seeded: &[SeededField], version: Option<&Version>) -> TokenStream {
+	let read = seeded
+		.iter()
+		.filter(|f| f.skip.is_none() && !f.flatten)
+		.collect::<Vec<_>>();
+	let version = match version {
+		Some(version) if read.iter().any(|f| f.versions.is_some()) => version,
+		_ => return quote_spanned!(Span::mixed_site()=> FIELD_NAMES.as_ref()),
+	};
+	let names_in = |v: u32| {
+		let names = read
+			.iter()
+			.filter(|f| !matches!(&f.versions, Some(versions) if !versions.contains(v)))
+			.map(|f| &f.name);
+		quote_spanned!(Span::mixed_site()=> &[#(#names,)*])
+	};
+	let versions = 0..version.current;
+	let earlier = versions.clone().map(names_in);
+	let current = names_in(version.current);
+	quote_spanned! {Span::mixed_site()=>
+		match version {
+			#(#versions => #earlier,)*
+			_ => #current,
+		}
+	}
+}
+
+/// The number of elements in the input of a tuple struct or variant, as expression.
+fn element_count<'a, 'b: 'a>(seeded: impl IntoIterator<Item = &'a SeededField<'b>>) -> TokenStream {
+	let read = seeded
+		.into_iter()
+		.filter(|f| f.skip.is_none())
+		.collect::<Vec<_>>();
+	let count = read.iter().filter(|f| f.versions.is_none()).count();
+	let conditions = read
+		.iter()
+		.filter_map(|f| f.versions.as_ref().map(FieldVersions::condition));
+	quote_spanned!(Span::mixed_site()=> #count #(+ #conditions as usize)*)
+}
+
+/// An expression evaluating to the [`DeserializeSeed`](serde::de::DeserializeSeed) for a value of type `ty`.
+///
+/// A custom seeder sees a reborrow of each mutable reference argument it mentions,
+/// so that it can consume that without using up the argument for the other fields.
+fn seed_expr(seeder: &Seeder, ty: &Type, args: &[FnArg]) -> TokenStream {
+	match seeder {
+		// Plain values deserialize through their own `Deserialize` implementation.
+		Seeder::Plain => quote_spanned!(ty.span()=> ::std::marker::PhantomData::<#ty>),
+		Seeder::Inherent { .. } => quote_spanned!(ty.span()=> <#ty>::seed()),
+		Seeder::Custom { span, seeder } => {
+			let serde_seeded = serde_seeded();
+			let seed = quote_spanned!(*span=> #serde_seeded::DeSeeder::<'_, #ty>::seed(#seeder));
+			let reborrowed = forwarded_args(args, None)
+				.into_iter()
+				.flatten()
+				.filter(|(arg, ty)| is_mutable_reference(ty) && refers_to(seeder.clone(), arg))
+				.map(|(arg, _)| arg)
+				.collect::<Vec<_>>();
+			if reborrowed.is_empty() {
+				return seed;
+			}
+			quote_spanned! {*span=> {
+				#(let #reborrowed = &mut *#reborrowed;)*
+				#seed
+			}}
+		}
+		// Like the seed arguments shared with the steps after deserializing.
+		Seeder::Forward { span, args: names } => {
+			let forwarded =
+				forwarded_args(args, names.as_deref())
+					.into_iter()
+					.map(|arg| match arg {
+						Ok((arg, ty)) if is_mutable_reference(ty) => {
+							quote_spanned!(Span::mixed_site()=> &mut *#arg)
+						}
+						Ok((arg, _)) => {
+							quote_spanned!(Span::mixed_site()=> ::std::clone::Clone::clone(&#arg))
+						}
+						Err(error) => error.to_compile_error(),
+					});
+			let serde_seeded = serde_seeded();
+			// Through the trait, since the seed function may be named differently.
+			quote_spanned!(*span=> <#ty as #serde_seeded::SeedWith<'_, _>>::seed_with((#(#forwarded,)*)))
+		}
+	}
+}
+
+/// Whether the seed that `seeder` evaluates to reborrows mutable reference arguments.
+fn reborrows(seeder: &Seeder, args: &[FnArg]) -> bool {
+	let names = match seeder {
+		Seeder::Plain | Seeder::Inherent { .. } => return false,
+		Seeder::Custom { seeder, .. } => {
+			return forwarded_args(args, None)
+				.into_iter()
+				.flatten()
+				.any(|(arg, ty)| is_mutable_reference(ty) && refers_to(seeder.clone(), arg))
+		}
+		Seeder::Forward { args: names, .. } => names,
+	};
+	forwarded_args(args, names.as_deref())
+		.into_iter()
+		.flatten()
+		.any(|(_, ty)| is_mutable_reference(ty))
+}
+
+fn is_mutable_reference(ty: &Type) -> bool {
+	matches!(
+		ty,
+		Type::Reference(TypeReference {
+			mutability: Some(_),
+			..
+		})
+	)
+}
+
+/// The value of a field that's missing because it's not part of the input's format version.
+fn absent(default: &Option<TokenStream>) -> TokenStream {
+	default
+		.clone()
+		.unwrap_or_else(|| quote_spanned!(Span::mixed_site()=> ::std::default::Default::default()))
+}
+
+/// Reads the attributes of `fields` for all profiles and checks them.
+///
+/// Seed arguments named in `args` take precedence over fields of the same name.
+fn parse_fields(
+	fields: &Fields,
+	profiles: &[Profile],
+	version: Option<&Version>,
+	args: &[Ident],
+	errors: &mut Vec<TokenStream>,
+) -> Vec<FieldAttrs> {
+	let mut parsed = vec![];
+	for (i, field) in fields.iter().enumerate() {
+		errors.extend(
+			field.attrs.iter()
+				.filter(|a| a.path.is_ident("seed_args"))
+				.map(|a| Error::new_spanned(a, "Misplaced #[seeded_args]: This attribute is only valid on the type's definition")
+					.to_compile_error()
+				)
+			);
+
+		let attrs = field::parse(field, Direction::De, profiles, version, errors);
+		if attrs.skip.is_some()
+			&& matches!(fields, Fields::Unnamed(fields) if fields.unnamed.len() == 1)
+		{
+			errors.push(
+				Error::new_spanned(
+					field,
+					"The field of a newtype struct or variant can't be skipped",
+				)
+				.to_compile_error(),
+			);
+		}
+		if attrs.flatten && field.ident.is_none() {
+			errors.push(
+				Error::new_spanned(field, "Only named fields can be flattened").to_compile_error(),
+			);
+		}
+
+		// Seeders can see the values of earlier fields, which are read in order.
+		for seeder in attrs.seeders() {
+			if let Seeder::Custom { seeder, .. } = seeder {
+				errors.extend(
+					fields
+						.iter()
+						.skip(i + 1)
+						.filter_map(|f| f.ident.as_ref())
+						.filter(|later| !args.contains(later) && refers_to(seeder.clone(), later))
+						.map(|later| {
+							Error::new_spanned(
+								seeder,
+								format!(
+									"Seeders can only refer to earlier fields, but `{}` comes later",
+									later
+								),
+							)
+							.to_compile_error()
+						}),
+				);
+			}
+		}
+		parsed.push(attrs);
+	}
+	parsed
+}
+
+fn seeded_fields<'a>(
+	fields: &'a Fields,
+	attrs: &[FieldAttrs],
+	rename_all: Option<RenameRule>,
+	profile: Option<&Profile>,
+	args: &[FnArg],
+) -> Vec<SeededField<'a>> {
+	let arg_idents = forwarded_args(args, None)
+		.into_iter()
+		.filter_map(|arg| arg.ok().map(|(ident, _)| ident))
+		.collect::<Vec<_>>();
+	let mut seeded = vec![];
+	for (i, (field, attrs)) in fields.iter().zip(attrs).enumerate() {
+		let local = Ident::new(&format!("__field{}", i), Span::mixed_site());
+		let name = match &field.ident {
+			Some(ident) => case::field_name(ident, rename_all),
+			None => LitStr::new(&i.to_string(), field.ty.span()),
+		};
+		let name = attrs.rename.clone().unwrap_or(name);
+
+		let ty = &field.ty;
+		let seeder = attrs.seeder(profile);
+		let seed = seed_expr(seeder, ty, args);
+
+		let default = match &attrs.default {
+			Some(Some(expr)) => Some(quote_spanned!(expr.span()=> #expr)),
+			Some(None) => Some(quote_spanned!(ty.span()=> ::std::default::Default::default())),
+			None if field::is_option(ty) => {
+				Some(quote_spanned!(ty.span()=> ::std::option::Option::None))
+			}
+			None => None,
+		};
+
+		let skip =
+			match &attrs.skip {
+				Some(Some(expr)) => Some(quote_spanned!(expr.span()=> #expr)),
+				Some(None) => Some(default.clone().unwrap_or_else(
+					|| quote_spanned!(ty.span()=> ::std::default::Default::default()),
+				)),
+				None => None,
+			};
+
+		// Skipped and flattened fields are only known once all others are read,
+		// and seed arguments take precedence over fields of the same name.
+		let dependencies = match seeder {
+			Seeder::Custom { seeder, .. } if skip.is_none() && !attrs.flatten => seeded
+				.iter()
+				.filter(|f: &&SeededField| f.skip.is_none() && !f.flatten)
+				.filter_map(|f| f.ident)
+				.filter(|ident| !arg_idents.contains(ident) && refers_to(seeder.clone(), ident))
+				.collect(),
+			_ => vec![],
+		};
+
+		let early = dependencies.is_empty() && !reborrows(seeder, args);
+		seeded.push(SeededField {
+			ident: field.ident.as_ref(),
+			local,
+			name,
+			aliases: attrs.aliases.clone(),
+			seed,
+			default,
+			skip,
+			flatten: attrs.flatten,
+			versions: attrs.versions.clone(),
+			validate: attrs.validate.clone(),
+			dependencies,
+			early,
+		});
 	}
+	seeded
 }
@@ -0,0 +1,548 @@
+use crate::{
+	profile::Profile,
+	version::{self, FieldVersions, Version},
+};
+use proc_macro2::{Span, TokenStream, TokenTree};
+use quote::ToTokens as _;
+use syn::{
+	parenthesized,
+	parse::{Parse, ParseStream, Parser as _},
+	parse_quote,
+	punctuated::Punctuated,
+	spanned::Spanned as _,
+	Attribute, Data, DeriveInput, Error, Expr, ExprLit, Field, Ident, Lit, LitStr, Token, Type,
+	TypePath, WherePredicate,
+};
+
+/// Which derive is reading the field attributes.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+	De,
+	Ser,
+}
+
+impl Direction {
+	fn attr_name(self) -> &'static str {
+		match self {
+			Direction::De => "seeded_de",
+			Direction::Ser => "seeded_ser",
+		}
+	}
+}
+
+/// How a field's value is (de)serialized.
+pub enum Seeder {
+	/// No seeder or `plain`: The field type's own serde implementation.
+	Plain,
+	/// Bare `#[seeded]`: The field type's derived `seed`/`seeded` method.
+	Inherent { span: Span },
+	/// `#[seeded(expr)]`, which may refer to earlier fields by name when deserializing.
+	Custom { span: Span, seeder: TokenStream },
+	/// `#[seeded(forward)]` or `#[seeded(forward(arg, ...))]`: The field type's derived method,
+	/// called with all of the container's seed arguments or with the named ones.
+	Forward {
+		span: Span,
+		args: Option<Vec<Ident>>,
+	},
+}
+
+/// The options given through a field's `#[seeded]` and direction-specific attributes.
+pub struct FieldAttrs {
+	pub seeder: Seeder,
+	/// The seeders given as `name = expr` for each profile, which replace the default one.
+	pub profile_seeders: Vec<(Ident, Seeder)>,
+	/// `default` (as [`None`]) or `default = expr`.
+	pub default: Option<Option<Expr>>,
+	/// `skip` (as [`None`]) or `skip = expr`, the latter giving the deserialized value.
+	pub skip: Option<Option<Expr>>,
+	/// `skip_if = expr`
+	pub skip_if: Option<Expr>,
+	/// `rename = "..."`
+	pub rename: Option<LitStr>,
+	/// Each `alias = "..."`.
+	pub aliases: Vec<LitStr>,
+	/// `flatten`: The field's entries are merged into the containing map.
+	pub flatten: bool,
+	/// `since = n` and `until = n`: The format versions the field appears in, if limited.
+	pub versions: Option<FieldVersions>,
+	/// `validate = path`: Checks the deserialized value.
+	pub validate: Option<Expr>,
+}
+
+enum Entry {
+	Plain(Ident),
+	/// `name` or `name = expr`, where the latter may also be a profile's seeder.
+	Option(Ident, Option<Expr>),
+	Seeder(Expr),
+}
+
+/// The names of options in field attributes, which can't be used as seeder expressions on their own.
+const OPTIONS: &[&str] = &[
+	"default", "skip", "skip_if", "rename", "alias", "flatten", "since", "until", "validate",
+];
+
+impl Parse for Entry {
+	fn parse(input: ParseStream) -> syn::Result<Self> {
+		let fork = input.fork();
+		if let Ok(ident) = fork.parse::<Ident>() {
+			let assigned = fork.peek(Token![=]) && !fork.peek(Token![==]);
+			if assigned || fork.is_empty() || fork.peek(Token![,]) {
+				if ident == "plain" && !assigned {
+					input.parse::<Ident>()?;
+					return Ok(Entry::Plain(ident));
+				} else if assigned || OPTIONS.iter().any(|option| ident == option) {
+					input.parse::<Ident>()?;
+					let value = if assigned {
+						input.parse::<Token![=]>()?;
+						Some(input.parse()?)
+					} else {
+						None
+					};
+					return Ok(Entry::Option(ident, value));
+				}
+				// Otherwise a plain variable as seeder.
+			}
+		}
+		input.parse().map(Entry::Seeder)
+	}
+}
+
+impl FieldAttrs {
+	/// The seeder to use for `profile`.
+	pub fn seeder(&self, profile: Option<&Profile>) -> &Seeder {
+		profile
+			.and_then(|profile| {
+				self.profile_seeders
+					.iter()
+					.find(|(name, _)| *name == profile.name)
+			})
+			.map_or(&self.seeder, |(_, seeder)| seeder)
+	}
+
+	/// The default seeder and those of the profiles.
+	pub fn seeders(&self) -> impl Iterator<Item = &Seeder> {
+		std::iter::once(&self.seeder).chain(self.profile_seeders.iter().map(|(_, seeder)| seeder))
+	}
+}
+
+/// Reads the field options from `#[seeded]` and `#[seeded_de]` or `#[seeded_ser]`, depending on `direction`,
+/// with the seeders of all `profiles`.
+pub fn parse(
+	field: &Field,
+	direction: Direction,
+	profiles: &[Profile],
+	version: Option<&Version>,
+	errors: &mut Vec<TokenStream>,
+) -> FieldAttrs {
+	let mut seeder = None;
+	let mut profile_seeders = Vec::<(Ident, Seeder)>::new();
+	let mut default = None;
+	let mut skip = None;
+	let mut skip_if = None;
+	let mut validate = None;
+	let mut rename = None;
+	let mut aliases = vec![];
+	let mut flatten = false;
+	let mut since = None;
+	let mut until = None;
+
+	let mut set_seeder = |seeder_: Seeder, span: Span, errors: &mut Vec<TokenStream>| {
+		if seeder.is_some() {
+			errors.push(Error::new(span, "Multiple seeders for the same field").to_compile_error());
+		}
+		seeder = Some(seeder_);
+	};
+
+	for attr in field
+		.attrs
+		.iter()
+		.filter(|a| a.path.is_ident("seeded") || a.path.is_ident(direction.attr_name()))
+	{
+		if attr.tokens.is_empty() {
+			set_seeder(
+				Seeder::Inherent {
+					span: attr.path.span(),
+				},
+				attr.span(),
+				errors,
+			);
+			continue;
+		}
+
+		let (paren, entries) = match parse_entries(attr) {
+			Ok(parsed) => parsed,
+			Err(error) => {
+				errors.push(error.to_compile_error());
+				continue;
+			}
+		};
+
+		let shared = attr.path.is_ident("seeded");
+		for entry in entries {
+			let (ident, value) = match entry {
+				Entry::Plain(ident) => {
+					set_seeder(Seeder::Plain, ident.span(), errors);
+					continue;
+				}
+				Entry::Seeder(expr) => {
+					if let Some(forward) = forward(&expr) {
+						match forward {
+							Ok(forward) => set_seeder(forward, expr.span(), errors),
+							Err(error) => errors.push(error.to_compile_error()),
+						}
+						continue;
+					}
+					set_seeder(
+						Seeder::Custom {
+							span: paren,
+							seeder: expr.to_token_stream(),
+						},
+						expr.span(),
+						errors,
+					);
+					continue;
+				}
+				Entry::Option(ident, value) => (ident, value),
+			};
+
+			if !OPTIONS.iter().any(|option| ident == option) {
+				match (profiles.iter().find(|p| p.name == ident), value) {
+					(Some(found), Some(value)) => {
+						if profile_seeders.iter().any(|(name, _)| *name == found.name) {
+							errors.push(
+								Error::new_spanned(
+									&ident,
+									format!("Multiple `{}` seeders for the same field", ident),
+								)
+								.to_compile_error(),
+							);
+						}
+						let seeder = match value {
+							Expr::Path(path) if path.path.is_ident("plain") => Seeder::Plain,
+							value => match forward(&value) {
+								Some(Ok(forward)) => forward,
+								Some(Err(error)) => {
+									errors.push(error.to_compile_error());
+									Seeder::Plain
+								}
+								None => Seeder::Custom {
+									span: paren,
+									seeder: value.to_token_stream(),
+								},
+							},
+						};
+						profile_seeders.push((found.name.clone(), seeder));
+					}
+					_ => errors.push(
+						Error::new_spanned(ident, "Unknown field option or profile")
+							.to_compile_error(),
+					),
+				}
+				continue;
+			}
+
+			// Which direction the option is limited to.
+			let only = match ident.to_string().as_str() {
+				"default" | "alias" | "validate" => Some(Direction::De),
+				// The value is only used when deserializing, but a shared `skip = expr` skips the field both ways.
+				"skip" if value.is_some() && !shared => Some(Direction::De),
+				"skip_if" => Some(Direction::Ser),
+				_ => None,
+			};
+
+			match only {
+				Some(only) if only != direction => {
+					if !shared {
+						errors.push(
+							Error::new_spanned(
+								&ident,
+								format!(
+									"`{}{}` is only valid in #[seeded] or #[{}]",
+									ident,
+									if value.is_some() { " = ..." } else { "" },
+									only.attr_name()
+								),
+							)
+							.to_compile_error(),
+						);
+					}
+					continue;
+				}
+				_ => (),
+			}
+
+			let slot = match ident.to_string().as_str() {
+				"default" => &mut default,
+				"skip" => &mut skip,
+				"skip_if" => &mut skip_if,
+				"validate" => &mut validate,
+				"rename" => &mut rename,
+				"alias" => {
+					aliases.extend(string_value(&ident, value, errors));
+					continue;
+				}
+				"since" | "until" => {
+					let slot = if ident == "since" {
+						&mut since
+					} else {
+						&mut until
+					};
+					if slot.is_some() {
+						errors.push(
+							Error::new_spanned(&ident, format!("Duplicate `{}`", ident))
+								.to_compile_error(),
+						);
+					}
+					*slot = version_value(&ident, value, errors).or(*slot);
+					continue;
+				}
+				"flatten" => {
+					if let Some(value) = value {
+						errors.push(
+							Error::new_spanned(value, "`flatten` takes no value")
+								.to_compile_error(),
+						);
+					}
+					flatten = true;
+					continue;
+				}
+				_ => unreachable!(),
+			};
+
+			if slot.is_some() {
+				errors.push(
+					Error::new_spanned(&ident, format!("Duplicate `{}`", ident)).to_compile_error(),
+				);
+			}
+			*slot = Some(value);
+		}
+	}
+
+	let skip_if = skip_if.and_then(|skip_if| {
+		skip_if.or_else(|| {
+			errors.push(
+				Error::new(
+					field.span(),
+					"`skip_if` requires a condition: `skip_if = expr`",
+				)
+				.to_compile_error(),
+			);
+			None
+		})
+	});
+
+	let validate = validate.and_then(|validate| {
+		validate.or_else(|| {
+			errors.push(
+				Error::new(
+					field.span(),
+					"`validate` requires a function: `validate = path`",
+				)
+				.to_compile_error(),
+			);
+			None
+		})
+	});
+
+	let rename =
+		rename.and_then(|rename| string_value(&Ident::new("rename", field.span()), rename, errors));
+
+	let versions = version::field_versions(field, since, until, version, errors);
+
+	FieldAttrs {
+		seeder: seeder.unwrap_or(Seeder::Plain),
+		profile_seeders,
+		default,
+		skip,
+		skip_if,
+		rename,
+		aliases,
+		flatten,
+		versions,
+		validate,
+	}
+}
+
+/// Reads `forward` or `forward(arg, ...)` in place of a seeder expression.
+fn forward(expr: &Expr) -> Option<syn::Result<Seeder>> {
+	match expr {
+		Expr::Path(path) if path.path.is_ident("forward") => Some(Ok(Seeder::Forward {
+			span: path.span(),
+			args: None,
+		})),
+		Expr::Call(call) if matches!(&*call.func, Expr::Path(path) if path.path.is_ident("forward")) => {
+			Some(
+				call.args
+					.iter()
+					.map(|arg| match arg {
+						Expr::Path(path) => path.path.get_ident().cloned().ok_or_else(|| {
+							Error::new_spanned(path, "Expected the name of a seed argument")
+						}),
+						_ => Err(Error::new_spanned(
+							arg,
+							"Expected the name of a seed argument",
+						)),
+					})
+					.collect::<syn::Result<_>>()
+					.map(|args| Seeder::Forward {
+						span: call.func.span(),
+						args: Some(args),
+					}),
+			)
+		}
+		_ => None,
+	}
+}
+
+/// Unpacks the string literal value of `option = "..."`.
+fn string_value(
+	option: &Ident,
+	value: Option<Expr>,
+	errors: &mut Vec<TokenStream>,
+) -> Option<LitStr> {
+	match value {
+		Some(Expr::Lit(ExprLit {
+			lit: Lit::Str(value),
+			..
+		})) => Some(value),
+		Some(value) => {
+			errors.push(Error::new_spanned(value, "Expected a string literal").to_compile_error());
+			None
+		}
+		None => {
+			errors.push(
+				Error::new_spanned(option, format!("Expected `{} = \"...\"`", option))
+					.to_compile_error(),
+			);
+			None
+		}
+	}
+}
+
+/// Unpacks the version number of `since = n` or `until = n`.
+fn version_value(
+	option: &Ident,
+	value: Option<Expr>,
+	errors: &mut Vec<TokenStream>,
+) -> Option<u32> {
+	match value {
+		Some(Expr::Lit(ExprLit {
+			lit: Lit::Int(value),
+			..
+		})) => value
+			.base10_parse()
+			.map_err(|error| errors.push(error.to_compile_error()))
+			.ok(),
+		Some(value) => {
+			errors.push(Error::new_spanned(value, "Expected a version number").to_compile_error());
+			None
+		}
+		None => {
+			errors.push(
+				Error::new_spanned(option, format!("Expected `{} = n`", option)).to_compile_error(),
+			);
+			None
+		}
+	}
+}
+
+/// Whether `attr` is a field attribute that hands every seed argument on,
+/// through a `validate` option or a `forward` seeder without a list.
+pub fn hands_on(attr: &Attribute) -> bool {
+	if !["seeded", "seeded_de", "seeded_ser"]
+		.iter()
+		.any(|name| attr.path.is_ident(name))
+		|| attr.tokens.is_empty()
+	{
+		return false;
+	}
+	let entries = match parse_entries(attr) {
+		Ok((_, entries)) => entries,
+		Err(_) => return false,
+	};
+	entries.into_iter().any(|entry| match entry {
+		Entry::Option(ident, _) if ident == "validate" => true,
+		// A profile's seeder.
+		Entry::Option(ident, Some(value)) if !OPTIONS.iter().any(|option| ident == option) => {
+			matches!(
+				forward(&value),
+				Some(Ok(Seeder::Forward { args: None, .. }))
+			)
+		}
+		Entry::Seeder(expr) => {
+			matches!(forward(&expr), Some(Ok(Seeder::Forward { args: None, .. })))
+		}
+		_ => false,
+	})
+}
+
+fn parse_entries(attr: &Attribute) -> syn::Result<(Span, Punctuated<Entry, Token![,]>)> {
+	// Unlike `call2`, this reports leftover tokens after a failed parse as error rather than panicking.
+	(|input: ParseStream| {
+		let content;
+		let paren = parenthesized!(content in input);
+		Ok((paren.span, Punctuated::parse_terminated(&content)?))
+	})
+	.parse2(attr.tokens.clone())
+}
+
+/// The where-predicates that require the types of plain fields to implement `bound` where they mention a type parameter,
+/// as serde_derive would infer them.
+///
+/// `attrs` are those of the struct's fields or of each variant's. Skipped fields don't need the bound.
+pub fn plain_bounds(
+	input: &DeriveInput,
+	attrs: &[Vec<FieldAttrs>],
+	profile: Option<&Profile>,
+	bound: &TokenStream,
+) -> Vec<WherePredicate> {
+	let params = input
+		.generics
+		.type_params()
+		.map(|param| &param.ident)
+		.collect::<Vec<_>>();
+	let fields: Vec<&Field> = match &input.data {
+		Data::Struct(data) => data.fields.iter().collect(),
+		Data::Enum(data) => data.variants.iter().flat_map(|v| v.fields.iter()).collect(),
+		Data::Union(_) => vec![],
+	};
+
+	let mut types = Vec::<&Type>::new();
+	let mut seen = Vec::<String>::new();
+	for (field, attrs) in fields.into_iter().zip(attrs.iter().flatten()) {
+		if matches!(attrs.seeder(profile), Seeder::Plain)
+			&& attrs.skip.is_none()
+			&& mentions_any(field.ty.to_token_stream(), &params)
+		{
+			let spelling = field.ty.to_token_stream().to_string();
+			if !seen.contains(&spelling) {
+				seen.push(spelling);
+				types.push(&field.ty);
+			}
+		}
+	}
+	types
+		.into_iter()
+		.map(|ty| parse_quote!(#ty: #bound))
+		.collect()
+}
+
+fn mentions_any(tokens: TokenStream, idents: &[&Ident]) -> bool {
+	tokens.into_iter().any(|token| match token {
+		TokenTree::Group(group) => mentions_any(group.stream(), idents),
+		TokenTree::Ident(ident) => idents.iter().any(|i| **i == ident),
+		_ => false,
+	})
+}
+
+/// Whether `ty` is spelled as [`Option`], in which case the field defaults to [`None`].
+pub fn is_option(ty: &Type) -> bool {
+	match ty {
+		Type::Path(TypePath { qself: None, path }) => {
+			matches!(path.segments.last(), Some(segment) if segment.ident == "Option")
+		}
+		Type::Group(group) => is_option(&group.elem),
+		Type::Paren(paren) => is_option(&paren.elem),
+		_ => false,
+	}
+}
@@ -1,11 +1,24 @@
 use proc_macro::TokenStream;
-use proc_macro2::Span;
-use quote::quote;
+use proc_macro2::{Punct, Spacing, Span, TokenTree};
+use quote::{quote, quote_spanned};
 use std::borrow::Cow;
-use syn::{parse_macro_input, DeriveInput, Ident};
+use syn::{
+	parenthesized,
+	parse::{ParseStream, Parser as _},
+	parse_macro_input, ConstParam, Data, DeriveInput, Error, FnArg, GenericArgument, GenericParam,
+	Ident, Lifetime, Pat, PatIdent, PatType, Path, PathArguments, Type, TypeParam, Visibility,
+	WherePredicate,
+};
 
+mod case;
+mod convert;
 mod de;
+mod field;
+mod profile;
 mod ser;
+mod tagging;
+mod unknown_fields;
+mod version;
 
 #[proc_macro_derive(
 	seed,
@@ -15,7 +28,23 @@ mod ser;
 		seed_args,
 		seed_args_de,
 		seed_generics,
-		seed_generics_de
+		seed_generics_de,
+		seed_where,
+		seed_where_de,
+		seed_rename_all,
+		seed_tag,
+		seed_content,
+		seed_untagged,
+		seed_unknown_fields,
+		seed_type,
+		seed_fn,
+		seed_profile,
+		seed_version,
+		seed_migrate,
+		seed_validate,
+		seed_finish,
+		seed_from,
+		seed_try_from
 	)
 )]
 pub fn seed(input: TokenStream) -> TokenStream {
@@ -33,7 +62,18 @@ pub fn seed(input: TokenStream) -> TokenStream {
 		seed_args,
 		seed_args_ser,
 		seed_generics,
-		seed_generics_ser
+		seed_generics_ser,
+		seed_where,
+		seed_where_ser,
+		seed_rename_all,
+		seed_tag,
+		seed_content,
+		seed_untagged,
+		seeded_type,
+		seeded_fn,
+		seed_profile,
+		seed_version,
+		seeded_into
 	)
 )]
 pub fn seeded(input: TokenStream) -> TokenStream {
@@ -50,3 +90,274 @@ fn serde_seeded() -> proc_macro2::TokenStream {
 	let ident = Ident::new(&name, Span::call_site());
 	quote!(::#ident)
 }
+
+/// Removes a type or const parameter's default, which is only allowed on the type's own definition.
+fn without_default(param: &GenericParam) -> GenericParam {
+	match param {
+		GenericParam::Type(ty) => GenericParam::Type(TypeParam {
+			eq_token: None,
+			default: None,
+			..ty.clone()
+		}),
+		GenericParam::Const(c) => GenericParam::Const(ConstParam {
+			eq_token: None,
+			default: None,
+			..c.clone()
+		}),
+		GenericParam::Lifetime(_) => param.clone(),
+	}
+}
+
+fn generic_param_ident(param: &GenericParam) -> &Ident {
+	match param {
+		GenericParam::Type(ty) => &ty.ident,
+		GenericParam::Const(c) => &c.ident,
+		GenericParam::Lifetime(l) => &l.lifetime.ident,
+	}
+}
+
+/// Reads a visibility and name as in `#[seed_type(pub FooSeed)]` or `#[seed_fn(pub(crate) load_with)]`,
+/// depending on `attr_name`.
+fn visibility_and_name(
+	input: &DeriveInput,
+	attr_name: &str,
+	errors: &mut Vec<proc_macro2::TokenStream>,
+) -> Option<(Visibility, Ident)> {
+	let mut named = None;
+	for attr in input.attrs.iter().filter(|a| a.path.is_ident(attr_name)) {
+		let parsed = (|input: ParseStream| {
+			let content;
+			parenthesized!(content in input);
+			Ok((content.parse()?, content.parse()?))
+		})
+		.parse2(attr.tokens.clone());
+
+		match parsed {
+			Ok(parsed) => {
+				if named.is_some() {
+					errors.push(
+						Error::new_spanned(attr, format!("Duplicate #[{}]", attr_name))
+							.to_compile_error(),
+					);
+				}
+				named = Some(parsed);
+			}
+			Err(error) => errors.push(error.to_compile_error()),
+		}
+	}
+	named
+}
+
+/// Reads the function paths of each `#[seed_validate(path::to::fn)]`, or another attribute named `attr_name`.
+fn hook_paths(
+	input: &DeriveInput,
+	attr_name: &str,
+	errors: &mut Vec<proc_macro2::TokenStream>,
+) -> Vec<Path> {
+	input
+		.attrs
+		.iter()
+		.filter(|a| a.path.is_ident(attr_name))
+		.filter_map(|attr| {
+			(|input: ParseStream| {
+				let content;
+				parenthesized!(content in input);
+				content.parse()
+			})
+			.parse2(attr.tokens.clone())
+			.map_err(|error| errors.push(error.to_compile_error()))
+			.ok()
+		})
+		.collect()
+}
+
+/// The where-clause of a seed's or view's `Clone` implementation, which needs each of the `args` to be `Clone`.
+///
+/// A plain `Arg: Clone` bound on an argument type that doesn't mention the generics,
+/// like `&mut T` or an owned type without `Clone`, would be trivially false and fail to compile.
+/// Below a `for<...>`, bounds aren't checked eagerly, so such arguments make the implementation unusable instead.
+fn clone_where(args: &[FnArg], predicates: &[&WherePredicate]) -> proc_macro2::TokenStream {
+	let arg_types = args.iter().filter_map(|arg| match arg {
+		FnArg::Receiver(_) => None,
+		FnArg::Typed(PatType { ty, .. }) => Some(ty),
+	});
+	quote_spanned! {Span::mixed_site()=>
+		where
+			#(for<'__clone> #arg_types: ::std::clone::Clone,)*
+			#(#predicates,)*
+	}
+}
+
+/// The names and types of the seed arguments passed on by `#[seeded(forward)]`,
+/// or of those named in `#[seeded(forward(...))]`.
+fn forwarded_args<'a>(
+	args: &'a [FnArg],
+	names: Option<&'a [Ident]>,
+) -> Vec<syn::Result<(&'a Ident, &'a Type)>> {
+	let named = |arg: &'a FnArg| match arg {
+		FnArg::Typed(PatType { pat, ty, .. }) => match &**pat {
+			Pat::Ident(PatIdent { ident, .. }) => Some((ident, &**ty)),
+			_ => None,
+		},
+		FnArg::Receiver(_) => None,
+	};
+	match names {
+		None => args
+			.iter()
+			.map(|arg| {
+				named(arg).ok_or_else(|| {
+					Error::new_spanned(arg, "Only arguments bound to a name can be forwarded")
+				})
+			})
+			.collect(),
+		Some(names) => names
+			.iter()
+			.map(|name| {
+				args.iter()
+					.filter_map(named)
+					.find(|(ident, _)| *ident == name)
+					.ok_or_else(|| {
+						Error::new_spanned(name, format!("Unknown seed argument `{}`", name))
+					})
+			})
+			.collect(),
+	}
+}
+
+/// Whether `tokens` mention the variable `ident`,
+/// rather than a path segment, method, field or struct literal label of that name.
+fn refers_to(tokens: proc_macro2::TokenStream, ident: &Ident) -> bool {
+	let mut previous = [None::<Punct>, None];
+	let mut tokens = tokens.into_iter().peekable();
+	while let Some(token) = tokens.next() {
+		match &token {
+			TokenTree::Group(group) if refers_to(group.stream(), ident) => return true,
+			TokenTree::Ident(candidate) if candidate == ident => {
+				let qualified = match &previous {
+					[Some(last), _] if last.as_char() == '.' => true,
+					[Some(last), Some(before)] => {
+						last.as_char() == ':'
+							&& before.as_char() == ':'
+							&& before.spacing() == Spacing::Joint
+					}
+					_ => false,
+				};
+				// Unlike a path separator, the colon after a label stands alone.
+				let label = matches!(
+					tokens.peek(),
+					Some(TokenTree::Punct(next)) if next.as_char() == ':' && next.spacing() == Spacing::Alone
+				);
+				if !qualified && !label {
+					return true;
+				}
+			}
+			_ => (),
+		}
+		previous = match token {
+			TokenTree::Punct(punct) => [Some(punct), previous[0].take()],
+			_ => [None, None],
+		};
+	}
+	false
+}
+
+/// The seed arguments that the attributes on `input` use, by name or by handing all of them on.
+/// Generated methods that don't need one of these acknowledge it, while arguments no attribute uses
+/// are reported as unused, like function parameters.
+fn used_args<'a>(input: &DeriveInput, arg_idents: &[&'a Ident]) -> Vec<&'a Ident> {
+	// Hooks and conversion functions receive every argument, as do field validators and `forward` without a list.
+	const HANDING_ON: &[&str] = &[
+		"seed_validate",
+		"seed_migrate",
+		"seed_finish",
+		"seed_from",
+		"seed_try_from",
+		"seeded_into",
+	];
+	let mut uses = input.attrs.iter().collect::<Vec<_>>();
+	match &input.data {
+		Data::Struct(data) => uses.extend(data.fields.iter().flat_map(|field| &field.attrs)),
+		Data::Enum(data) => {
+			for variant in &data.variants {
+				uses.extend(&variant.attrs);
+				uses.extend(variant.fields.iter().flat_map(|field| &field.attrs));
+			}
+		}
+		Data::Union(_) => (),
+	}
+	// The declarations don't count.
+	uses.retain(|attr| {
+		!["seed_args", "seed_args_de", "seed_args_ser", "seed_profile"]
+			.iter()
+			.any(|name| attr.path.is_ident(name))
+	});
+	if uses
+		.iter()
+		.any(|attr| HANDING_ON.iter().any(|name| attr.path.is_ident(name)) || field::hands_on(attr))
+	{
+		return arg_idents.to_vec();
+	}
+	arg_idents
+		.iter()
+		.copied()
+		.filter(|ident| {
+			uses.iter()
+				.any(|attr| refers_to(attr.tokens.clone(), ident))
+		})
+		.collect()
+}
+
+/// Names the elided and `'_` lifetimes in the arguments' types through `name`,
+/// since the arguments are stored in a struct where they can't be elided.
+fn name_elided_lifetimes(args: &mut [FnArg], name: &mut dyn FnMut(Span) -> Lifetime) {
+	fn visit_lifetime(
+		lifetime: &mut Option<Lifetime>,
+		span: Span,
+		name: &mut dyn FnMut(Span) -> Lifetime,
+	) {
+		match lifetime {
+			Some(l) if l.ident != "_" => (),
+			_ => *lifetime = Some(name(span)),
+		}
+	}
+
+	fn visit_type(ty: &mut Type, name: &mut dyn FnMut(Span) -> Lifetime) {
+		match ty {
+			Type::Reference(reference) => {
+				visit_lifetime(&mut reference.lifetime, reference.and_token.span, name);
+				visit_type(&mut reference.elem, name);
+			}
+			Type::Array(array) => visit_type(&mut array.elem, name),
+			Type::Group(group) => visit_type(&mut group.elem, name),
+			Type::Paren(paren) => visit_type(&mut paren.elem, name),
+			Type::Ptr(ptr) => visit_type(&mut ptr.elem, name),
+			Type::Slice(slice) => visit_type(&mut slice.elem, name),
+			Type::Tuple(tuple) => tuple
+				.elems
+				.iter_mut()
+				.for_each(|elem| visit_type(elem, name)),
+			Type::Path(path) => {
+				for segment in path.path.segments.iter_mut() {
+					if let PathArguments::AngleBracketed(arguments) = &mut segment.arguments {
+						for argument in arguments.args.iter_mut() {
+							match argument {
+								GenericArgument::Type(ty) => visit_type(ty, name),
+								GenericArgument::Lifetime(lifetime) if lifetime.ident == "_" => {
+									*lifetime = name(lifetime.apostrophe)
+								}
+								_ => (),
+							}
+						}
+					}
+				}
+			}
+			_ => (),
+		}
+	}
+
+	for arg in args {
+		if let FnArg::Typed(PatType { ty, .. }) = arg {
+			visit_type(ty, name);
+		}
+	}
+}
@@ -0,0 +1,122 @@
+use proc_macro2::{TokenStream, TokenTree};
+use quote::ToTokens as _;
+use syn::{
+	parenthesized,
+	parse::{ParseStream, Parser as _},
+	punctuated::{Pair, Punctuated},
+	DeriveInput, Error, FnArg, Ident, PatType, Token,
+};
+
+/// A named variant of the generated methods, from `#[seed_profile(name, args(...))]`.
+pub struct Profile {
+	pub name: Ident,
+	/// Seed arguments in addition to the `#[seed_args]`.
+	pub args: Vec<FnArg>,
+	/// Whether no other profile's `args` have the same types,
+	/// so that this profile's methods can implement `SeedWith` and `SeededWith` without overlapping.
+	///
+	/// Types are compared by spelling only, since macros can't resolve them,
+	/// so aliases of or other paths to the same type aren't caught here.
+	pub distinct_args: bool,
+}
+
+/// Reads each `#[seed_profile(name)]` or `#[seed_profile(name, args(...))]`.
+pub fn parse(input: &DeriveInput, errors: &mut Vec<TokenStream>) -> Vec<Profile> {
+	let mut profiles = Vec::<Profile>::new();
+	for attr in input
+		.attrs
+		.iter()
+		.filter(|a| a.path.is_ident("seed_profile"))
+	{
+		let parsed = (|input: ParseStream| {
+			let content;
+			parenthesized!(content in input);
+			let name: Ident = content.parse()?;
+			let mut args = vec![];
+			if !content.is_empty() {
+				content.parse::<Token![,]>()?;
+				let keyword: Ident = content.parse()?;
+				if keyword != "args" {
+					return Err(Error::new_spanned(keyword, "Expected `args(...)`"));
+				}
+				let inner;
+				parenthesized!(inner in content);
+				args.extend(
+					Punctuated::<FnArg, Token![,]>::parse_terminated(&inner)?
+						.into_pairs()
+						.map(Pair::into_value),
+				);
+				if let Some(FnArg::Receiver(receiver)) =
+					args.iter().find(|arg| matches!(arg, FnArg::Receiver(_)))
+				{
+					return Err(Error::new_spanned(
+						receiver,
+						"self-parameters are not supported in this position",
+					));
+				}
+				content.parse::<Option<Token![,]>>()?;
+			}
+			Ok(Profile {
+				name,
+				args,
+				distinct_args: true,
+			})
+		})
+		.parse2(attr.tokens.clone());
+
+		match parsed {
+			Ok(profile) => {
+				if profiles.iter().any(|p| p.name == profile.name) {
+					errors.push(
+						Error::new_spanned(&profile.name, "Duplicate #[seed_profile] name")
+							.to_compile_error(),
+					);
+				} else {
+					profiles.push(profile);
+				}
+			}
+			Err(error) => errors.push(error.to_compile_error()),
+		}
+	}
+
+	let keys = profiles
+		.iter()
+		.map(|profile| arg_types_key(&profile.args))
+		.collect::<Vec<_>>();
+	for (profile, key) in profiles.iter_mut().zip(keys.iter()) {
+		profile.distinct_args = keys.iter().filter(|other| *other == key).count() == 1;
+	}
+	profiles
+}
+
+/// The spelling of the argument types, without lifetimes since implementations are generic over those.
+fn arg_types_key(args: &[FnArg]) -> Vec<String> {
+	fn without_lifetimes(tokens: TokenStream, key: &mut String) {
+		let mut tokens = tokens.into_iter();
+		while let Some(token) = tokens.next() {
+			match token {
+				TokenTree::Punct(punct) if punct.as_char() == '\'' => {
+					tokens.next();
+					continue;
+				}
+				TokenTree::Group(group) => {
+					key.push_str(&format!("{:?} ", group.delimiter()));
+					without_lifetimes(group.stream(), key);
+					key.push_str("end");
+				}
+				token => key.push_str(&token.to_string()),
+			}
+			key.push(' ');
+		}
+	}
+	args.iter()
+		.filter_map(|arg| match arg {
+			FnArg::Typed(PatType { ty, .. }) => {
+				let mut key = String::new();
+				without_lifetimes(ty.to_token_stream(), &mut key);
+				Some(key)
+			}
+			FnArg::Receiver(_) => None,
+		})
+		.collect()
+}
@@ -1,41 +1,75 @@
-use crate::serde_seeded;
+use crate::{
+	case::{self, RenameRule},
+	clone_where,
+	convert::{self, Conversion},
+	field::{self, Direction, FieldAttrs, Seeder},
+	forwarded_args, generic_param_ident, name_elided_lifetimes,
+	profile::{self, Profile},
+	refers_to, serde_seeded,
+	tagging::{self, Tagging},
+	used_args,
+	version::{self, Version, VersionSource},
+	visibility_and_name, without_default,
+};
 use call2_for_syn::call2;
 use proc_macro2::{Span, TokenStream};
-use quote::{quote, quote_spanned, ToTokens as _};
-use std::borrow::Cow;
+use quote::{quote, quote_spanned, ToTokens};
 use syn::{
-	parenthesized, parse2,
+	ext::IdentExt as _,
+	parenthesized, parse2, parse_quote,
 	punctuated::{Pair, Punctuated},
 	spanned::Spanned as _,
-	Data, DeriveInput, Error, FnArg, GenericParam, Generics, Ident, Lifetime, PatType, Token,
+	Data, DeriveInput, Error, Fields, FnArg, GenericParam, Generics, Ident, Lifetime, LitStr,
+	PatType, Token, Visibility, WherePredicate,
 };
 use wyz::TapOption;
 
 pub fn expand_derive(input: &DeriveInput) -> syn::Result<TokenStream> {
-	let name = &input.ident;
-	let serde_seeded = serde_seeded();
 	let mut errors = vec![];
-
-	let mut type_generics_lifetimes = vec![];
-	let mut type_generics_types = vec![];
-	for generic in input.generics.params.iter() {
-		match generic{
-		    syn::GenericParam::Type(ty) => type_generics_types.push(ty),
-		    syn::GenericParam::Lifetime(l) => type_generics_lifetimes.push(l),
-		    syn::GenericParam::Const(c) => {errors.push(Error::new_spanned(c, "serde-seeded::seeded: Const parameters are currently not supported here. You can request or help out with implementation at <https://github.com/Tamschi/serde-seeded/issues/2>.").to_compile_error())}
-		}
+	let profiles = profile::parse(input, &mut errors);
+	// Only the seed arguments and seeders differ between profiles, so everything else is read (and checked) once.
+	let shared = parse_shared(input, &profiles, &mut errors)?;
+	if profiles.is_empty() {
+		let items = expand(input, &shared, None);
+		return Ok(quote!(#(#errors)* #items));
 	}
 
-	let type_generics_lifetime_lifetimes = type_generics_lifetimes
-		.iter()
-		.map(|l| &l.lifetime)
-		.collect::<Vec<_>>();
-	let type_generics_type_idents = type_generics_types
+	if let Some(attr) = input.attrs.iter().find(|a| a.path.is_ident("seeded_type")) {
+		errors.push(
+			Error::new_spanned(
+				attr,
+				"#[seeded_type] can't be combined with #[seed_profile]",
+			)
+			.to_compile_error(),
+		);
+	}
+	let items = profiles
 		.iter()
-		.map(|t| &t.ident)
-		.collect::<Vec<_>>();
-	let type_generics_where = &input.generics.where_clause;
+		.map(|profile| expand(input, &shared, Some(profile)));
+	Ok(quote!(#(#errors)* #(#items)*))
+}
+
+/// The input's attributes, which all profiles share.
+struct Shared {
+	fn_generics: Vec<Generics>,
+	/// The `#[seed_args]`, to which a profile adds its own.
+	args: Vec<FnArg>,
+	fn_where: Vec<WherePredicate>,
+	rename_all: Option<RenameRule>,
+	version: Option<Version>,
+	tagging: Tagging,
+	conversion: Option<Conversion>,
+	seeded_fn: Option<(Visibility, Ident)>,
+	seeded_type: Option<(Visibility, Ident)>,
+	/// The attributes of the struct's fields, or of each variant's.
+	fields: Vec<Vec<FieldAttrs>>,
+}
 
+fn parse_shared(
+	input: &DeriveInput,
+	profiles: &[Profile],
+	errors: &mut Vec<TokenStream>,
+) -> syn::Result<Shared> {
 	let fn_generics = input
 		.attrs
 		.iter()
@@ -52,35 +86,27 @@ pub fn expand_derive(input: &DeriveInput) -> syn::Result<TokenStream> {
 		})
 		.collect::<Vec<_>>();
 
-	let mut fn_generics_lifetimes = vec![];
-	let mut fn_generics_types = vec![];
-	for generic in fn_generics.iter().flat_map(|g| g.params.iter()) {
-		match generic {
-				GenericParam::Type(ty) => fn_generics_types.push(ty),
-				GenericParam::Lifetime(l) => fn_generics_lifetimes.push(l),
-				GenericParam::Const(c) => {errors.push(Error::new_spanned(c, "serde-seeded::seed: Const parameters are currently not supported here. You can request or help out with implementation at <https://github.com/Tamschi/serde-seeded/issues/3>.").to_compile_error())}
-			}
-	}
-
-	let fn_generics_lifetime_lifetimes = fn_generics_lifetimes
-		.iter()
-		.map(|l| &l.lifetime)
-		.collect::<Vec<_>>();
-	let fn_generics_type_idents = fn_generics_types
+	let fn_where = input
+		.attrs
 		.iter()
-		.map(|t| &t.ident)
+		.filter(|a| a.path.is_ident("seed_where") || a.path.is_ident("seed_where_ser"))
+		.filter_map(|a| {
+			call2(a.tokens.clone(), |input| {
+				let predicates;
+				parenthesized!(predicates in input);
+				let predicates =
+					Punctuated::<WherePredicate, Token![,]>::parse_terminated(&predicates)?
+						.into_pairs()
+						.map(Pair::into_value);
+				Ok(predicates)
+			})
+			.map_err(|e| errors.push(e.to_compile_error()))
+			.ok()
+		})
+		.flatten()
 		.collect::<Vec<_>>();
-	// Where clauses on derived functions are missing too but don't have a specific error since there's no syntax to specify them yet. The GitHub issue is <https://github.com/Tamschi/serde-seeded/issues/4>.
 
-	let mut default_ser = vec![Lifetime::new("'ser", Span::mixed_site())];
-	let ser = fn_generics_lifetime_lifetimes
-		.iter()
-		.copied()
-		.find(|l| l.ident == "ser")
-		.tap_some(|_| default_ser.pop().unwrap())
-		.unwrap_or_else(|| default_ser.first().unwrap());
-
-	let args = input
+	let mut args = input
 		.attrs
 		.iter()
 		.filter(|a| a.path.is_ident("seed_args") || a.path.is_ident("seed_args_ser"))
@@ -98,174 +124,926 @@ pub fn expand_derive(input: &DeriveInput) -> syn::Result<TokenStream> {
 		})
 		.flatten()
 		.collect::<Vec<_>>();
+	args.retain(|arg| match arg {
+		FnArg::Receiver(r) => {
+			errors.push(
+				Error::new_spanned(r, "self-parameters are not supported in this position")
+					.to_compile_error(),
+			);
+			false
+		}
+		FnArg::Typed(_) => true,
+	});
 
-	let arg_names = args
+	let rename_all = case::parse_rename_all(input, errors);
+	let version = version::parse(input, errors);
+	let tagging = tagging::parse(input, errors);
+	let conversion = convert::parse(input, &[("seeded_into", false)], errors);
+	let seeded_fn = visibility_and_name(input, "seeded_fn", errors);
+	let seeded_type = visibility_and_name(input, "seeded_type", errors);
+
+	let fields = match &input.data {
+		Data::Struct(data) => vec![parse_fields(&data.fields, profiles, version.as_ref(), errors)],
+		Data::Enum(data) => {
+			let mut fields = vec![];
+			for variant in data.variants.iter() {
+				errors.extend(
+					variant.attrs.iter()
+						.filter(|a| a.path.is_ident("seeded") || a.path.is_ident("seeded_ser"))
+						.map(|a| Error::new_spanned(a, "Misplaced #[seeded] or #[seeded_ser]: This attribute is only valid on fields")
+							.to_compile_error()
+						)
+					);
+
+				let attrs = parse_fields(&variant.fields, profiles, version.as_ref(), errors);
+				errors.extend(variant.fields.iter().zip(attrs.iter()).filter(|(_, f)| f.flatten).map(|(field, _)| {
+					Error::new_spanned(field, "Fields of enum variants can't be flattened").to_compile_error()
+				}));
+				if let (Tagging::Internal { .. }, Fields::Unnamed(unnamed)) = (&tagging, &variant.fields) {
+					if unnamed.unnamed.len() != 1 {
+						errors.push(Error::new_spanned(variant, "Tuple variants are not supported in internally tagged enums").to_compile_error());
+					}
+				}
+				fields.push(attrs);
+			}
+			fields
+		}
+		Data::Union(u) => return Err(Error::new_spanned(u.union_token, "serde-seeded derive macros are not available on unions yet. You can request this feature at <https://github.com/Tamschi/serde-seeded/issues/6>.")),
+	};
+
+	Ok(Shared {
+		fn_generics,
+		args,
+		fn_where,
+		rename_all,
+		version,
+		tagging,
+		conversion,
+		seeded_fn,
+		seeded_type,
+		fields,
+	})
+}
+
+/// Expands the derive once, for the given profile or for none.
+fn expand(input: &DeriveInput, shared: &Shared, profile: Option<&Profile>) -> TokenStream {
+	let name = &input.ident;
+	let serde_seeded = serde_seeded();
+
+	let mut type_generics_lifetimes = vec![];
+	let mut type_generics_params = vec![];
+	for generic in input.generics.params.iter() {
+		match generic {
+			GenericParam::Lifetime(l) => type_generics_lifetimes.push(l),
+			GenericParam::Type(_) | GenericParam::Const(_) => {
+				type_generics_params.push(without_default(generic))
+			}
+		}
+	}
+
+	let type_generics_lifetime_lifetimes = type_generics_lifetimes
 		.iter()
-		.map(|arg| match arg {
-			FnArg::Receiver(r) => {
-				Error::new_spanned(r, "self-parameters are not supported in this position")
-					.to_compile_error()
+		.map(|l| &l.lifetime)
+		.collect::<Vec<_>>();
+	let type_generics_param_idents = type_generics_params
+		.iter()
+		.map(generic_param_ident)
+		.collect::<Vec<_>>();
+
+	let mut fn_generics_lifetimes = vec![];
+	let mut fn_generics_params = vec![];
+	for generic in shared.fn_generics.iter().flat_map(|g| g.params.iter()) {
+		match generic {
+			GenericParam::Lifetime(l) => fn_generics_lifetimes.push(l),
+			GenericParam::Type(_) | GenericParam::Const(_) => {
+				fn_generics_params.push(without_default(generic))
 			}
-			FnArg::Typed(PatType { pat, .. }) => pat.into_token_stream(),
+		}
+	}
+
+	let fn_generics_lifetime_lifetimes = fn_generics_lifetimes
+		.iter()
+		.map(|l| &l.lifetime)
+		.collect::<Vec<_>>();
+	let fn_generics_param_idents = fn_generics_params
+		.iter()
+		.map(generic_param_ident)
+		.collect::<Vec<_>>();
+	let fn_generics_type_idents = fn_generics_params
+		.iter()
+		.filter_map(|p| match p {
+			GenericParam::Type(ty) => Some(&ty.ident),
+			_ => None,
+		})
+		.collect::<Vec<_>>();
+
+	let mut fn_where = shared.fn_where.clone();
+	// The fields of a converted type aren't written themselves.
+	if shared.conversion.is_none() {
+		fn_where.extend(field::plain_bounds(
+			input,
+			&shared.fields,
+			profile,
+			&quote!(#serde_seeded::serde::Serialize),
+		));
+	}
+
+	let where_clause = &input.generics.where_clause;
+	let seeded_where = {
+		let predicates = where_clause
+			.iter()
+			.flat_map(|w| w.predicates.iter())
+			.chain(fn_where.iter());
+		quote!(where #(#predicates,)*)
+	};
+
+	let mut default_ser = vec![Lifetime::new("'ser", Span::mixed_site())];
+	let ser = fn_generics_lifetime_lifetimes
+		.iter()
+		.copied()
+		.find(|l| l.ident == "ser")
+		.tap_some(|_| default_ser.pop().unwrap())
+		.unwrap_or_else(|| default_ser.first().unwrap());
+
+	let mut args = shared.args.clone();
+	if let Some(profile) = profile {
+		args.extend(profile.args.iter().cloned());
+	}
+	// The arguments are kept in the result, which can't outlive the serialization lifetime anyway.
+	name_elided_lifetimes(&mut args, &mut |span| Lifetime::new(&ser.to_string(), span));
+
+	let arg_names = args
+		.iter()
+		.filter_map(|arg| match arg {
+			FnArg::Receiver(_) => None,
+			FnArg::Typed(PatType { pat, .. }) => Some(pat),
 		})
 		.collect::<Vec<_>>();
+	// Not every variant uses every argument the attributes do.
+	let arg_idents = forwarded_args(&args, None)
+		.into_iter()
+		.filter_map(|arg| arg.ok().map(|(ident, _)| ident))
+		.collect::<Vec<_>>();
+	let arg_idents = used_args(input, &arg_idents);
+
+	let impl_generics = quote! {
+		#(#default_ser,)*
+		#(#type_generics_lifetimes,)*
+		#(#fn_generics_lifetimes,)*
+		#(#type_generics_params,)*
+		#(#fn_generics_params,)*
+	};
+	let (fn_vis, mut fn_ident) = shared
+		.seeded_fn
+		.clone()
+		.unwrap_or_else(|| (parse_quote!(pub), Ident::new("seeded", Span::call_site())));
+	if let Some(profile) = profile {
+		fn_ident = Ident::new(
+			&format!("{}_{}", fn_ident, profile.name),
+			profile.name.span(),
+		);
+	}
+
+	// A named serializable type is declared next to the input. Otherwise, it's hidden in an anonymous constant,
+	// but as visible as the input so that it can appear in the `SeededWith` implementation.
+	let named = match profile {
+		None => shared.seeded_type.as_ref(),
+		Some(_) => None,
+	};
+	let (seeded_vis, seeded_ident) = match &named {
+		Some((vis, ident)) => (vis.to_token_stream(), ident.clone()),
+		None => (
+			input.vis.to_token_stream(),
+			Ident::new("Seeded", Span::mixed_site()),
+		),
+	};
+	let seeded_ty = quote_spanned! {Span::mixed_site()=>
+		#seeded_ident<
+			#(#default_ser,)*
+			#(#type_generics_lifetime_lifetimes,)*
+			#(#fn_generics_lifetime_lifetimes,)*
+			#(#type_generics_param_idents,)*
+			#(#fn_generics_param_idents,)*
+		>
+	};
 
-	match &input.data {
+	let mut untagged_impl = None;
+	let rename_all = shared.rename_all;
+	let version = &shared.version;
+	let serialize = match &input.data {
 		Data::Struct(data) => {
-			let field_count = data.fields.len();
-
-			let mut field_idents = vec![];
-			let mut serialize_fields = vec![];
-			for (i, field) in data.fields.iter().enumerate() {
-				let ident = field
-					.ident
-					.as_ref()
-					.map(Cow::Borrowed)
-					.unwrap_or_else(|| Cow::Owned(Ident::new(&i.to_string(), field.ty.span())));
-
-				field_idents.push(ident.clone());
-
-				let mut attrs: Vec<_> = field
-					.attrs
-					.iter()
-					.filter(|a| a.path.is_ident("seeded") || a.path.is_ident("seeded_ser"))
-					.collect();
-
-				if attrs.len() > 1 {
-					let mut attrs = attrs.split_off(1).into_iter().map(|a| {
-						Error::new_spanned(
-							a,
-							"Multiple #[seeded] or #[seeded_ser] attributes on the same field",
-						)
-					});
-					let mut first = attrs.next().unwrap();
-					for next in attrs {
-						first.combine(next);
+			let seeded = seeded_fields(
+				&data.fields,
+				&shared.fields[0],
+				rename_all,
+				profile,
+				version.as_ref(),
+				&args,
+			);
+			let pattern = destructure(quote!(#name), &data.fields, &seeded);
+			let field_count = serialized_len(&seeded);
+			let serialize_struct_fields = serialize_fields(
+				quote!(ser::SerializeStruct),
+				"serialize_field",
+				"serialize_struct",
+				&seeded,
+				true,
+			);
+			let serialize_tuple_struct_fields = serialize_fields(
+				quote!(ser::SerializeTupleStruct),
+				"serialize_field",
+				"serialize_tuple_struct",
+				&seeded,
+				false,
+			);
+
+			let body = match &data.fields {
+				// Flattened fields add an unknown number of entries, so only a map will do.
+				Fields::Named(_) if seeded.iter().any(|f| f.flatten) => {
+					let serialize_map_entries = serialize_map_entries(name, &seeded);
+					quote_spanned! {Span::mixed_site()=>
+						let mut serialize_map = serializer.serialize_map(::std::option::Option::None)?;
+						#serialize_map_entries
+						ser::SerializeMap::end(serialize_map)
+					}
+				}
+				Fields::Named(_) => quote_spanned! {Span::mixed_site()=>
+					let mut serialize_struct = serializer.serialize_struct(stringify!(#name), #field_count)?;
+					#serialize_struct_fields
+					ser::SerializeStruct::end(serialize_struct)
+				},
+				Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+					let serialize = &seeded[0].serialize;
+					quote_spanned! {Span::mixed_site()=>
+						serializer.serialize_newtype_struct(stringify!(#name), #serialize)
 					}
-					errors.push(first.to_compile_error())
 				}
+				Fields::Unnamed(_) => quote_spanned! {Span::mixed_site()=>
+					let mut serialize_tuple_struct = serializer.serialize_tuple_struct(stringify!(#name), #field_count)?;
+					#serialize_tuple_struct_fields
+					ser::SerializeTupleStruct::end(serialize_tuple_struct)
+				},
+				Fields::Unit => quote_spanned! {Span::mixed_site()=>
+					serializer.serialize_unit_struct(stringify!(#name))
+				},
+			};
 
-				let attr = attrs.drain(..).next();
-				assert_eq!(attrs.into_iter().count(), 0);
+			quote_spanned! {Span::mixed_site()=>
+				let #pattern = **__this;
+				#body
+			}
+		}
+		Data::Enum(data) => {
+			let tagging = &shared.tagging;
 
-				let serialize =
-				if let Some(attr) = attr {
-					if attr.tokens.is_empty() {
-						quote_spanned!(attr.path.span()=> &#ident.seeded())
-					} else {
-						let tokens = &attr.tokens;
-
-						call2(quote!(#tokens), |tokens| {
-							let content;
-							let paren = parenthesized!(content in tokens);
-							Ok((paren, content.parse()?))
-						})
-						.map_or_else(
-							|error| {
-								errors.push(error.to_compile_error());
-								quote_spanned!(attr.path.span()=> ())
-							},
-							|(paren, custom_seeder): (_, TokenStream)| {
-								quote_spanned!(paren.span.resolved_at(Span::mixed_site())=> { // <-- No-field-shadowing!-brace.
-									&#custom_seeder.seeded(#ident)
-								})
-							},
-						)
+			let mut arms = vec![];
+			let mut untagged_arms = vec![];
+			for (i, (variant, attrs)) in data.variants.iter().zip(shared.fields.iter()).enumerate()
+			{
+				let variant_ident = &variant.ident;
+				let variant_index = i as u32;
+				let variant_name = case::variant_name(variant_ident, rename_all);
+
+				let seeded = seeded_fields(
+					&variant.fields,
+					attrs,
+					None,
+					profile,
+					version.as_ref(),
+					&args,
+				);
+				let pattern = destructure(quote!(#name::#variant_ident), &variant.fields, &seeded);
+				let len = serialized_len(&seeded);
+				let serialize_struct_fields = serialize_fields(
+					quote!(ser::SerializeStruct),
+					"serialize_field",
+					"serialize_struct",
+					&seeded,
+					true,
+				);
+				let serialize_tuple_elements = serialize_fields(
+					quote!(ser::SerializeTuple),
+					"serialize_element",
+					"serialize_tuple",
+					&seeded,
+					false,
+				);
+				let serialize_tuple_variant_fields = serialize_fields(
+					quote!(ser::SerializeTupleVariant),
+					"serialize_field",
+					"serialize_tuple_variant",
+					&seeded,
+					false,
+				);
+				let serialize_struct_variant_fields = serialize_fields(
+					quote!(ser::SerializeStructVariant),
+					"serialize_field",
+					"serialize_struct_variant",
+					&seeded,
+					true,
+				);
+
+				let untagged = match &variant.fields {
+					Fields::Unit => quote_spanned! {Span::mixed_site()=>
+						serializer.serialize_unit()
+					},
+					Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+						let serialize = &seeded[0].serialize;
+						quote_spanned! {Span::mixed_site()=>
+							ser::Serialize::serialize(#serialize, serializer)
+						}
 					}
-				} else {
-					errors.push(Error::new_spanned(field, "#[seeded] or #[seeded_ser] required").to_compile_error());
-					quote_spanned!(field.span()=> &())
+					Fields::Unnamed(_) => quote_spanned! {Span::mixed_site()=>
+						let mut serialize_tuple = serializer.serialize_tuple(#len)?;
+						#serialize_tuple_elements
+						ser::SerializeTuple::end(serialize_tuple)
+					},
+					Fields::Named(_) => quote_spanned! {Span::mixed_site()=>
+						let mut serialize_struct = serializer.serialize_struct(stringify!(#name), #len)?;
+						#serialize_struct_fields
+						ser::SerializeStruct::end(serialize_struct)
+					},
 				};
 
-				serialize_fields.push(
-					quote_spanned! {ident.span().resolved_at(Span::mixed_site())=>
-						serialize_struct.serialize_field(stringify!(#ident), #serialize)?;
+				let body = match (&tagging, &variant.fields) {
+					(Tagging::External, Fields::Unit) => quote_spanned! {Span::mixed_site()=>
+						serializer.serialize_unit_variant(stringify!(#name), #variant_index, #variant_name)
 					},
-				)
+					(Tagging::External, Fields::Unnamed(fields)) if fields.unnamed.len() == 1 => {
+						let serialize = &seeded[0].serialize;
+						quote_spanned! {Span::mixed_site()=>
+							serializer.serialize_newtype_variant(stringify!(#name), #variant_index, #variant_name, #serialize)
+						}
+					}
+					(Tagging::External, Fields::Unnamed(_)) => quote_spanned! {Span::mixed_site()=>
+						let mut serialize_tuple_variant = serializer.serialize_tuple_variant(stringify!(#name), #variant_index, #variant_name, #len)?;
+						#serialize_tuple_variant_fields
+						ser::SerializeTupleVariant::end(serialize_tuple_variant)
+					},
+					(Tagging::External, Fields::Named(_)) => quote_spanned! {Span::mixed_site()=>
+						let mut serialize_struct_variant = serializer.serialize_struct_variant(stringify!(#name), #variant_index, #variant_name, #len)?;
+						#serialize_struct_variant_fields
+						ser::SerializeStructVariant::end(serialize_struct_variant)
+					},
+					(Tagging::Internal { tag }, Fields::Unnamed(fields))
+						if fields.unnamed.len() == 1 =>
+					{
+						let serialize = &seeded[0].serialize;
+						quote_spanned! {Span::mixed_site()=>
+							ser::Serialize::serialize(#serialize, #serde_seeded::__private::TaggedSerializer {
+								type_ident: stringify!(#name),
+								variant_ident: stringify!(#variant_ident),
+								tag: #tag,
+								variant_name: #variant_name,
+								delegate: serializer,
+							})
+						}
+					}
+					// Rejected by `parse_shared`.
+					(Tagging::Internal { tag }, Fields::Unnamed(_)) => {
+						quote_spanned! {Span::mixed_site()=>
+							let mut serialize_struct = serializer.serialize_struct(stringify!(#name), 1)?;
+							ser::SerializeStruct::serialize_field(&mut serialize_struct, #tag, #variant_name)?;
+							ser::SerializeStruct::end(serialize_struct)
+						}
+					}
+					(Tagging::Internal { tag }, Fields::Unit)
+					| (Tagging::Internal { tag }, Fields::Named(_)) => quote_spanned! {Span::mixed_site()=>
+						let mut serialize_struct = serializer.serialize_struct(stringify!(#name), #len + 1)?;
+						ser::SerializeStruct::serialize_field(&mut serialize_struct, #tag, #variant_name)?;
+						#serialize_struct_fields
+						ser::SerializeStruct::end(serialize_struct)
+					},
+					(Tagging::Adjacent { tag, .. }, Fields::Unit) => {
+						quote_spanned! {Span::mixed_site()=>
+							let mut serialize_struct = serializer.serialize_struct(stringify!(#name), 1)?;
+							ser::SerializeStruct::serialize_field(&mut serialize_struct, #tag, #variant_name)?;
+							ser::SerializeStruct::end(serialize_struct)
+						}
+					}
+					(Tagging::Adjacent { tag, content }, _) => quote_spanned! {Span::mixed_site()=>
+						let mut serialize_struct = serializer.serialize_struct(stringify!(#name), 2)?;
+						ser::SerializeStruct::serialize_field(&mut serialize_struct, #tag, #variant_name)?;
+						ser::SerializeStruct::serialize_field(&mut serialize_struct, #content, &Untagged(self))?;
+						ser::SerializeStruct::end(serialize_struct)
+					},
+					(Tagging::Untagged, _) => untagged.clone(),
+				};
+
+				arms.push(quote_spanned! {Span::mixed_site()=>
+					#pattern => {
+						#body
+					}
+				});
+				untagged_arms.push(quote_spanned! {Span::mixed_site()=>
+					#pattern => {
+						#untagged
+					}
+				});
 			}
 
-			Ok(quote_spanned! {Span::mixed_site()=>
-				#(#errors)*
-				#[automatically_derived]
-				impl<
-					#(#type_generics_lifetimes,)*
-					#(#type_generics_types,)*
-				> #name<
-					#(#type_generics_lifetime_lifetimes,)*
-					#(#type_generics_type_idents,)*
-				> #type_generics_where {
-					pub fn seeded<
-						#(#default_ser,)*
-						#(#fn_generics_lifetimes,)*
-						#(#fn_generics_types,)*
-					>(&#ser self, #(#args,)*) -> impl #ser + #serde_seeded::serde::Serialize {
-
-						//TODO: Use fully qualified calls instead.
-						use #serde_seeded::{
-							DeSeeder as _,
-							SerSeeder as _,
-							serde::{
-								ser::{self, SerializeStruct as _},
-								export::Result,
-							},
-						};
-
-						struct Seeded<
-							#(#default_ser,)*
-							#(#type_generics_lifetimes,)*
-							#(#fn_generics_lifetimes,)*
-							#(#type_generics_types,)*
-							#(#fn_generics_types,)*
-						> {
-							__this: &#ser #name<
-								#(#type_generics_lifetime_lifetimes,)*
-								#(#type_generics_type_idents,)*
-							>,
-							#(#args,)*
-							__phantom: ::std::marker::PhantomData<(
-								#(&#default_ser (),)*
-								#(&#type_generics_lifetime_lifetimes (),)*
-								#(&#fn_generics_lifetime_lifetimes (),)*
-							)>,
-						};
-						impl<
-							#(#default_ser,)*
-							#(#type_generics_lifetimes,)*
-							#(#fn_generics_lifetimes,)*
-							#(#type_generics_types,)*
-							#(#fn_generics_types,)*
-						> ser::Serialize for Seeded<
-							#(#default_ser,)*
-							#(#type_generics_lifetime_lifetimes,)*
-							#(#fn_generics_lifetime_lifetimes,)*
-							#(#type_generics_type_idents,)*
-							#(#fn_generics_type_idents,)*
-						> #type_generics_where {
-							fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
-								let mut serialize_struct = serializer.serialize_struct(stringify!(#name), #field_count)?;
-								let Seeded {
-									__this: #name {
-										#(#field_idents,)*
-									},
-									#(#arg_names,)*
-									__phantom: _,
-								} = &self;
-
-								#(#serialize_fields)*
-
-								serialize_struct.end()
+			if let Tagging::Adjacent { .. } = tagging {
+				// The content of an adjacently tagged variant is serialized as if untagged.
+				untagged_impl = Some(quote_spanned! {Span::mixed_site()=>
+					struct Untagged<'a, T>(&'a T);
+					impl<#impl_generics> ser::Serialize for Untagged<'_, #seeded_ty> #seeded_where {
+						fn serialize<__S: ser::Serializer>(&self, serializer: __S) -> ::std::result::Result<__S::Ok, __S::Error> {
+							let #seeded_ident {
+								__this,
+								#(#arg_names,)*
+								__phantom: _,
+							} = self.0;
+							#(let _ = &#arg_idents;)*
+
+							match **__this {
+								#(#untagged_arms)*
 							}
 						}
-						Seeded {
-							__this: self,
-							#(#arg_names,)*
-							__phantom: ::std::marker::PhantomData,
-						}
 					}
+				});
+			}
+
+			quote_spanned! {Span::mixed_site()=>
+				match **__this {
+					#(#arms)*
 				}
-			})
+			}
+		}
+		// Rejected by `parse_shared`.
+		Data::Union(_) => unreachable!(),
+	};
+
+	// A conversion replaces writing the fields.
+	let serialize = match &shared.conversion {
+		None => serialize,
+		Some(Conversion {
+			raw, with, seeder, ..
+		}) => {
+			// The seeder and wrapper are bound so that they're dropped before `raw`, which they borrow.
+			let value = match seeder {
+				Seeder::Plain => quote_spanned!(Span::mixed_site()=> let value = &raw;),
+				Seeder::Inherent { span } => {
+					quote_spanned!(span.resolved_at(Span::mixed_site())=> let value = raw.seeded();)
+				}
+				Seeder::Custom { span, seeder } => {
+					quote_spanned! {span.resolved_at(Span::mixed_site())=>
+						let seeder = #seeder;
+						let value = seeder.seeded(&raw);
+					}
+				}
+				Seeder::Forward { span, args: names } => {
+					let forwarded = forwarded(&args, names.as_deref());
+					quote_spanned! {span.resolved_at(Span::mixed_site())=>
+						let value = #serde_seeded::SeededWith::seeded_with(&raw, (#(#forwarded,)*));
+					}
+				}
+			};
+			quote_spanned! {Span::mixed_site()=>
+				let raw: #raw = #with(__this, #(#arg_names),*);
+				#value
+				ser::Serialize::serialize(&value, serializer)
+			}
+		}
+	};
+
+	let arg_types = args
+		.iter()
+		.filter_map(|arg| match arg {
+			FnArg::Receiver(_) => None,
+			FnArg::Typed(PatType { ty, .. }) => Some(ty),
+		})
+		.collect::<Vec<_>>();
+	let where_predicates = where_clause
+		.iter()
+		.flat_map(|w| w.predicates.iter())
+		.collect::<Vec<_>>();
+
+	let serialize = quote_spanned! {Span::mixed_site()=>
+		//TODO: Use fully qualified calls instead.
+		use #serde_seeded::{
+			DeSeeder as _,
+			SerSeeder as _,
+			serde::ser::{self, Serializer as _},
+		};
+
+		#untagged_impl
+
+		let Self {
+			__this,
+			#(#arg_names,)*
+			__phantom: _,
+		} = self;
+		#(let _ = &#arg_idents;)*
+
+		#serialize
+	};
+	// With a version header, the value is serialized as body after it.
+	let serialize_impl = match version {
+		Some(Version {
+			current,
+			source: VersionSource::Header,
+			..
+		}) => quote_spanned! {Span::mixed_site()=>
+			impl<#impl_generics> #serde_seeded::serde::Serialize for #seeded_ty #seeded_where {
+				fn serialize<__S: #serde_seeded::serde::Serializer>(&self, serializer: __S) -> ::std::result::Result<__S::Ok, __S::Error> {
+					#serde_seeded::__private::serialize_versioned(#current, self, serializer)
+				}
+			}
+			impl<#impl_generics> #serde_seeded::__private::SerializeBody for #seeded_ty #seeded_where {
+				fn serialize_body<__S: #serde_seeded::serde::Serializer>(&self, serializer: __S) -> ::std::result::Result<__S::Ok, __S::Error> {
+					#serialize
+				}
+			}
+		},
+		Some(Version {
+			source: VersionSource::Arg(_),
+			..
+		})
+		| None => quote_spanned! {Span::mixed_site()=>
+			impl<#impl_generics> #serde_seeded::serde::Serialize for #seeded_ty #seeded_where {
+				fn serialize<__S: #serde_seeded::serde::Serializer>(&self, serializer: __S) -> ::std::result::Result<__S::Ok, __S::Error> {
+					#serialize
+				}
+			}
+		},
+	};
+
+	let mut seeded_item = quote_spanned! {Span::mixed_site()=>
+		#seeded_vis struct #seeded_ident<#impl_generics> #where_clause {
+			__this: &#ser #name<
+				#(#type_generics_lifetime_lifetimes,)*
+				#(#type_generics_param_idents,)*
+			>,
+			#(#args,)*
+			__phantom: ::std::marker::PhantomData<(
+				#(&#default_ser (),)*
+				#(&#type_generics_lifetime_lifetimes (),)*
+				#(&#fn_generics_lifetime_lifetimes (),)*
+			)>,
+		}
+		#serialize_impl
+	};
+	let seeded_return = if named.is_some() {
+		let doc = format!(
+			"The serializable view returned by [`{}::{}`].",
+			name, fn_ident
+		);
+		let clone_where = clone_where(&args, &where_predicates);
+		seeded_item = quote_spanned! {Span::mixed_site()=>
+			#[doc = #doc]
+			#seeded_item
+
+			#[automatically_derived]
+			impl<#impl_generics> ::std::clone::Clone for #seeded_ty #clone_where {
+				fn clone(&self) -> Self {
+					let Self {
+						__this,
+						#(#arg_names,)*
+						__phantom: _,
+					} = self;
+					Self {
+						__this,
+						#(#arg_names: ::std::clone::Clone::clone(#arg_names),)*
+						__phantom: ::std::marker::PhantomData,
+					}
+				}
+			}
+
+			#[automatically_derived]
+			impl<#impl_generics> ::std::fmt::Debug for #seeded_ty #where_clause {
+				fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+					f.debug_struct(stringify!(#seeded_ident)).finish_non_exhaustive()
+				}
+			}
+		};
+		seeded_ty.clone()
+	} else {
+		quote!(impl #ser + #serde_seeded::serde::Serialize)
+	};
+
+	// Profiles that share their argument types with another would implement the trait twice, so they don't.
+	let distinct_args = !matches!(profile, Some(profile) if !profile.distinct_args);
+	let seeded_with = distinct_args.then(|| {
+		quote_spanned! {Span::mixed_site()=>
+			#[automatically_derived]
+			impl<#impl_generics> #serde_seeded::SeededWith<#ser, (#(#arg_types,)*)> for #name<
+				#(#type_generics_lifetime_lifetimes,)*
+				#(#type_generics_param_idents,)*
+			>
+			where
+				Self: #ser,
+				#(#fn_generics_type_idents: #ser,)*
+				#(#where_predicates,)*
+				#(#fn_where,)*
+			{
+				type Seeded = #seeded_ty;
+				fn seeded_with(&#ser self, (#(#arg_names,)*): (#(#arg_types,)*)) -> Self::Seeded {
+					#seeded_ident {
+						__this: self,
+						#(#arg_names,)*
+						__phantom: ::std::marker::PhantomData,
+					}
+				}
+			}
 		}
-		Data::Enum(e) => Err(Error::new_spanned(e.enum_token, "serde-seeded derive macros are not available on enums yet. You can request this feature at <https://github.com/Tamschi/serde-seeded/issues/5>.")),
-		Data::Union(u) => Err(Error::new_spanned(u.union_token, "serde-seeded derive macros are not available on unions yet. You can request this feature at <https://github.com/Tamschi/serde-seeded/issues/6>.")),
+	});
+
+	let items = quote_spanned! {Span::mixed_site()=>
+		#seeded_item
+		#seeded_with
+
+		#[automatically_derived]
+		impl<
+			#(#type_generics_lifetimes,)*
+			#(#type_generics_params,)*
+		> #name<
+			#(#type_generics_lifetime_lifetimes,)*
+			#(#type_generics_param_idents,)*
+		> #where_clause {
+			#fn_vis fn #fn_ident<
+				#(#default_ser,)*
+				#(#fn_generics_lifetimes,)*
+				#(#fn_generics_params,)*
+			>(&#ser self, #(#args,)*) -> #seeded_return
+			where
+				// The arguments are kept in the result.
+				#(#fn_generics_type_idents: #ser,)*
+				#(#fn_where,)*
+			{
+				#seeded_ident {
+					__this: self,
+					#(#arg_names,)*
+					__phantom: ::std::marker::PhantomData,
+				}
+			}
+		}
+	};
+
+	if named.is_some() {
+		items
+	} else {
+		quote! {
+			const _: () = {
+				#items
+			};
+		}
+	}
+}
+
+struct SeededField<'a> {
+	/// The field's identifier, if it's named.
+	ident: Option<&'a Ident>,
+	/// The hygienic local the field is destructured into, so that it can't hide seed arguments.
+	local: Ident,
+	/// The field's name as passed to the [`Serializer`](serde::Serializer).
+	name: LitStr,
+	/// An expression evaluating to a reference to the field's [`Serialize`](serde::Serialize) stand-in.
+	serialize: TokenStream,
+	/// Whether the field is left out of the output.
+	skip: Skip,
+	/// Whether the field's entries are merged into the containing map.
+	flatten: bool,
+}
+
+/// Expressions that pass on the seed arguments for `#[seeded(forward)]`, cloned from their bindings.
+fn forwarded(args: &[FnArg], names: Option<&[Ident]>) -> Vec<TokenStream> {
+	forwarded_args(args, names)
+		.into_iter()
+		.map(|arg| match arg {
+			Ok((arg, _)) => quote_spanned!(Span::mixed_site()=> ::std::clone::Clone::clone(#arg)),
+			Err(error) => error.to_compile_error(),
+		})
+		.collect()
+}
+
+enum Skip {
+	Never,
+	Always,
+	/// If the expression evaluates to `true`.
+	If(TokenStream),
+}
+
+impl Skip {
+	/// When a field with `attrs` is left out of the output.
+	fn of(attrs: &FieldAttrs, version: Option<&Version>) -> Self {
+		match (&attrs.skip, &attrs.skip_if) {
+			(Some(_), _) => Skip::Always,
+			// Only the current format version is written.
+			_ if matches!((&attrs.versions, version), (Some(versions), Some(version)) if !versions.contains(version.current)) => {
+				Skip::Always
+			}
+			(None, Some(condition)) => Skip::If(quote_spanned!(condition.span()=> #condition)),
+			(None, None) => Skip::Never,
+		}
+	}
+}
+
+/// Reads the attributes of `fields` for all profiles and checks them.
+fn parse_fields(
+	fields: &Fields,
+	profiles: &[Profile],
+	version: Option<&Version>,
+	errors: &mut Vec<TokenStream>,
+) -> Vec<FieldAttrs> {
+	let mut parsed = vec![];
+	for field in fields.iter() {
+		let attrs = field::parse(field, Direction::Ser, profiles, version, errors);
+		if !matches!(Skip::of(&attrs, version), Skip::Never)
+			&& matches!(fields, Fields::Unnamed(fields) if fields.unnamed.len() == 1)
+		{
+			errors.push(
+				Error::new_spanned(
+					field,
+					"The field of a newtype struct or variant can't be skipped",
+				)
+				.to_compile_error(),
+			);
+		}
+		if attrs.flatten && field.ident.is_none() {
+			errors.push(
+				Error::new_spanned(field, "Only named fields can be flattened").to_compile_error(),
+			);
+		}
+		parsed.push(attrs);
+	}
+	parsed
+}
+
+fn seeded_fields<'a>(
+	fields: &'a Fields,
+	attrs: &[FieldAttrs],
+	rename_all: Option<RenameRule>,
+	profile: Option<&Profile>,
+	version: Option<&Version>,
+	args: &[FnArg],
+) -> Vec<SeededField<'a>> {
+	let arg_idents = forwarded_args(args, None)
+		.into_iter()
+		.filter_map(|arg| arg.ok().map(|(ident, _)| ident))
+		.collect::<Vec<_>>();
+	let local_of = |i: usize| Ident::new(&format!("__field{}", i), Span::mixed_site());
+	// Seeders and conditions see the fields under their own names,
+	// but seed arguments take precedence over fields of the same name.
+	let bindings = |tokens: &TokenStream| {
+		let (idents, locals): (Vec<_>, Vec<_>) = fields
+			.iter()
+			.enumerate()
+			.filter_map(|(i, field)| field.ident.as_ref().map(|ident| (ident, local_of(i))))
+			.filter(|(ident, _)| !arg_idents.contains(ident) && refers_to(tokens.clone(), ident))
+			.unzip();
+		quote_spanned!(Span::mixed_site()=> #(let #idents = #locals;)*)
+	};
+
+	let mut seeded = vec![];
+	for (i, (field, attrs)) in fields.iter().zip(attrs).enumerate() {
+		let local = local_of(i);
+		let name = match &field.ident {
+			Some(ident) => case::field_name(ident, rename_all),
+			None => LitStr::new(&i.to_string(), field.ty.span()),
+		};
+		let name = attrs.rename.clone().unwrap_or(name);
+
+		let serialize = match attrs.seeder(profile) {
+			// Plain fields serialize through their own `Serialize` implementation.
+			Seeder::Plain => {
+				quote_spanned!(field.ty.span().resolved_at(Span::mixed_site())=> #local)
+			}
+			Seeder::Inherent { span } => {
+				quote_spanned!(span.resolved_at(Span::mixed_site())=> &#local.seeded())
+			}
+			Seeder::Forward { span, args: names } => {
+				let forwarded = forwarded(args, names.as_deref());
+				let serde_seeded = serde_seeded();
+				// Through the trait, since the seeded function may be named differently.
+				quote_spanned!(span.resolved_at(Span::mixed_site())=> &#serde_seeded::SeededWith::seeded_with(#local, (#(#forwarded,)*)))
+			}
+			Seeder::Custom { span, seeder } => {
+				let bindings = bindings(&quote!(#seeder));
+				quote_spanned!(span.resolved_at(Span::mixed_site())=> { // <-- No-field-shadowing!-brace.
+					#bindings
+					&#seeder.seeded(#local)
+				})
+			}
+		};
+
+		let skip = match Skip::of(attrs, version) {
+			Skip::If(condition) => {
+				let bindings = bindings(&condition);
+				Skip::If(quote_spanned!(Span::mixed_site()=> { #bindings #condition }))
+			}
+			skip => skip,
+		};
+
+		seeded.push(SeededField {
+			ident: field.ident.as_ref(),
+			local,
+			name,
+			serialize,
+			skip,
+			flatten: attrs.flatten,
+		});
 	}
+	seeded
+}
+
+/// A pattern that binds each field of `path` by reference.
+fn destructure(path: TokenStream, fields: &Fields, seeded: &[SeededField]) -> TokenStream {
+	let locals = seeded.iter().map(|f| &f.local);
+	match fields {
+		Fields::Named(_) => {
+			let idents = seeded.iter().map(|f| f.ident);
+			quote!(#path { #(#idents: ref #locals,)* })
+		}
+		Fields::Unnamed(_) => quote!(#path(#(ref #locals,)*)),
+		Fields::Unit => path,
+	}
+}
+
+/// The number of fields that are serialized, as expression.
+fn serialized_len(seeded: &[SeededField]) -> TokenStream {
+	let count = seeded
+		.iter()
+		.filter(|f| !matches!(f.skip, Skip::Always))
+		.count();
+	let conditions = seeded.iter().filter_map(|f| match &f.skip {
+		Skip::If(condition) => Some(condition),
+		Skip::Never | Skip::Always => None,
+	});
+	quote_spanned!(Span::mixed_site()=> #count #(- (#conditions) as usize)*)
+}
+
+/// Serializes the fields that aren't skipped into the local `state` through `trait_`'s `method`.
+/// Skipped `named` fields are reported through `skip_field`.
+fn serialize_fields(
+	trait_: TokenStream,
+	method: &str,
+	state: &str,
+	seeded: &[SeededField],
+	named: bool,
+) -> TokenStream {
+	let method = Ident::new(method, Span::mixed_site());
+	let state = Ident::new(state, Span::mixed_site());
+	let fields = seeded.iter().map(
+		|SeededField {
+		     name,
+		     serialize,
+		     skip,
+		     ..
+		 }| {
+			let (serialize, skip_field) = if named {
+				(
+					quote_spanned!(Span::mixed_site()=> #trait_::#method(&mut #state, #name, #serialize)?;),
+					quote_spanned!(Span::mixed_site()=> #trait_::skip_field(&mut #state, #name)?;),
+				)
+			} else {
+				(
+					quote_spanned!(Span::mixed_site()=> #trait_::#method(&mut #state, #serialize)?;),
+					quote!(),
+				)
+			};
+			match skip {
+				Skip::Never => serialize,
+				Skip::Always => skip_field,
+				Skip::If(condition) => quote_spanned! {Span::mixed_site()=>
+					if #condition {
+						#skip_field
+					} else {
+						#serialize
+					}
+				},
+			}
+		},
+	);
+	quote!(#(#fields)*)
+}
+
+/// Serializes the fields that aren't skipped as entries of the local `serialize_map`,
+/// with flattened fields contributing their own entries instead.
+fn serialize_map_entries(type_ident: &Ident, seeded: &[SeededField]) -> TokenStream {
+	let serde_seeded = serde_seeded();
+	let entries = seeded.iter().map(|SeededField { ident, name, serialize, skip, flatten, .. }| {
+		let serialize = match ident.filter(|_| *flatten) {
+			Some(ident) => {
+			let field_ident = LitStr::new(&ident.unraw().to_string(), ident.span());
+			quote_spanned! {Span::mixed_site()=>
+				ser::Serialize::serialize(#serialize, #serde_seeded::__private::FlatMapSerializer {
+					type_ident: stringify!(#type_ident),
+					field_ident: #field_ident,
+					map: &mut serialize_map,
+				})?;
+			}
+			}
+			None => quote_spanned!(Span::mixed_site()=> ser::SerializeMap::serialize_entry(&mut serialize_map, #name, #serialize)?;),
+		};
+		match skip {
+			Skip::Never => serialize,
+			Skip::Always => quote!(),
+			Skip::If(condition) => quote_spanned! {Span::mixed_site()=>
+				if !(#condition) {
+					#serialize
+				}
+			},
+		}
+	});
+	quote!(#(#entries)*)
 }
@@ -0,0 +1,103 @@
+use proc_macro2::TokenStream;
+use syn::{Data, DeriveInput, Error, Lit, LitStr, Meta, MetaNameValue};
+
+/// How an enum's variants are told apart on the wire, mirroring serde's enum representations.
+pub enum Tagging {
+	/// `{"Variant": content}`
+	External,
+	/// `{"tag": "Variant", ...content}`
+	Internal { tag: LitStr },
+	/// `{"tag": "Variant", "content": content}`
+	Adjacent { tag: LitStr, content: LitStr },
+	/// `content`
+	Untagged,
+}
+
+/// Reads `#[seed_tag = "..."]`, `#[seed_content = "..."]` and `#[seed_untagged]`.
+pub fn parse(input: &DeriveInput, errors: &mut Vec<TokenStream>) -> Tagging {
+	let mut tag = None;
+	let mut content = None;
+	let mut untagged = None;
+	for attr in input.attrs.iter() {
+		let (slot, name) = if attr.path.is_ident("seed_tag") {
+			(&mut tag, "seed_tag")
+		} else if attr.path.is_ident("seed_content") {
+			(&mut content, "seed_content")
+		} else if attr.path.is_ident("seed_untagged") {
+			if untagged.is_some() {
+				errors.push(
+					Error::new_spanned(attr, "Duplicate #[seed_untagged]").to_compile_error(),
+				);
+			}
+			untagged = Some(attr);
+			continue;
+		} else {
+			continue;
+		};
+
+		match attr.parse_meta() {
+			Ok(Meta::NameValue(MetaNameValue {
+				lit: Lit::Str(value),
+				..
+			})) => {
+				if slot.is_some() {
+					errors.push(
+						Error::new_spanned(attr, format!("Duplicate #[{}]", name))
+							.to_compile_error(),
+					);
+				}
+				*slot = Some(value);
+			}
+			Ok(_) => errors.push(
+				Error::new_spanned(attr, format!("Expected #[{} = \"...\"]", name))
+					.to_compile_error(),
+			),
+			Err(error) => errors.push(error.to_compile_error()),
+		}
+	}
+
+	if let Some(untagged) = untagged {
+		if !untagged.tokens.is_empty() {
+			errors
+				.push(Error::new_spanned(untagged, "Expected #[seed_untagged]").to_compile_error());
+		}
+	}
+
+	if !matches!(input.data, Data::Enum(_)) {
+		for attr in input.attrs.iter().filter(|a| {
+			a.path.is_ident("seed_tag")
+				|| a.path.is_ident("seed_content")
+				|| a.path.is_ident("seed_untagged")
+		}) {
+			errors.push(
+				Error::new_spanned(attr, "Enum representations can only be chosen for enums")
+					.to_compile_error(),
+			);
+		}
+		return Tagging::External;
+	}
+
+	match (tag, content, untagged) {
+		(None, None, None) => Tagging::External,
+		(Some(tag), None, None) => Tagging::Internal { tag },
+		(Some(tag), Some(content), None) => Tagging::Adjacent { tag, content },
+		(None, None, Some(_)) => Tagging::Untagged,
+		(None, Some(content), _) => {
+			errors.push(
+				Error::new_spanned(content, "#[seed_content] requires #[seed_tag]")
+					.to_compile_error(),
+			);
+			Tagging::External
+		}
+		(_, _, Some(untagged)) => {
+			errors.push(
+				Error::new_spanned(
+					untagged,
+					"#[seed_untagged] can't be combined with #[seed_tag]",
+				)
+				.to_compile_error(),
+			);
+			Tagging::Untagged
+		}
+	}
+}
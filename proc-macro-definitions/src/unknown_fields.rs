@@ -0,0 +1,62 @@
+use proc_macro2::TokenStream;
+use syn::{
+	parenthesized,
+	parse::{ParseStream, Parser as _},
+	DeriveInput, Error, Expr, Ident, Token,
+};
+
+/// What a derived seed does with map keys that don't name a field.
+pub enum UnknownFields {
+	/// Skip over the value.
+	Ignore,
+	/// Fail with [`de::Error::unknown_field`](serde::de::Error::unknown_field).
+	Deny,
+	/// Call the expression with the key and the value as [`Content`](serde_seeded::content::Content).
+	With(Box<Expr>),
+}
+
+/// Reads `#[seed_unknown_fields(ignore)]`, `#[seed_unknown_fields(deny)]` or `#[seed_unknown_fields(with = expr)]`.
+pub fn parse(input: &DeriveInput, errors: &mut Vec<TokenStream>) -> UnknownFields {
+	let mut policy = None;
+	for attr in input
+		.attrs
+		.iter()
+		.filter(|a| a.path.is_ident("seed_unknown_fields"))
+	{
+		let parsed = (|input: ParseStream| {
+			let content;
+			parenthesized!(content in input);
+			let ident: Ident = content.parse()?;
+			let parsed = match ident.to_string().as_str() {
+				"ignore" => UnknownFields::Ignore,
+				"deny" => UnknownFields::Deny,
+				"with" => {
+					content.parse::<Token![=]>()?;
+					UnknownFields::With(Box::new(content.parse()?))
+				}
+				_ => {
+					return Err(Error::new_spanned(
+						ident,
+						"Expected `ignore`, `deny` or `with = expr`",
+					))
+				}
+			};
+			Ok(parsed)
+		})
+		.parse2(attr.tokens.clone());
+
+		match parsed {
+			Ok(parsed) => {
+				if policy.is_some() {
+					errors.push(
+						Error::new_spanned(attr, "Duplicate #[seed_unknown_fields]")
+							.to_compile_error(),
+					);
+				}
+				policy = Some(parsed);
+			}
+			Err(error) => errors.push(error.to_compile_error()),
+		}
+	}
+	policy.unwrap_or(UnknownFields::Ignore)
+}
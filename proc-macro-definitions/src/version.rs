@@ -0,0 +1,211 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote_spanned;
+use syn::{
+	parenthesized,
+	parse::{ParseStream, Parser as _},
+	DeriveInput, Error, Expr, Field, Ident, LitInt, Path, Token,
+};
+
+/// The format version from `#[seed_version(...)]`, with its `#[seed_migrate(...)]` steps.
+pub struct Version {
+	/// The version that's serialized.
+	pub current: u32,
+	pub source: VersionSource,
+	/// Sorted by [`Migration::from`].
+	pub migrations: Vec<Migration>,
+}
+
+/// Where a derived seed gets the version of its input from.
+pub enum VersionSource {
+	/// `#[seed_version(n)]`: The value is (de)serialized as tuple after its version.
+	Header,
+	/// `#[seed_version(n, arg = expr)]`: The expression evaluates to the version, with the seed arguments in scope.
+	/// Serialization always uses the current format then, without writing the version.
+	Arg(Box<Expr>),
+}
+
+/// `#[seed_migrate(from = n, with = path)]`: Upgrades a value read in version `from` (or earlier) to `from + 1`.
+pub struct Migration {
+	pub from: u32,
+	pub with: Path,
+}
+
+/// Reads `#[seed_version(n)]` or `#[seed_version(n, arg = expr)]` and each `#[seed_migrate(from = n, with = path)]`.
+pub fn parse(input: &DeriveInput, errors: &mut Vec<TokenStream>) -> Option<Version> {
+	let mut version = None::<Version>;
+	for attr in input
+		.attrs
+		.iter()
+		.filter(|a| a.path.is_ident("seed_version"))
+	{
+		let parsed = (|input: ParseStream| {
+			let content;
+			parenthesized!(content in input);
+			let current = content.parse::<LitInt>()?.base10_parse()?;
+			let mut source = VersionSource::Header;
+			if !content.is_empty() {
+				content.parse::<Token![,]>()?;
+				let keyword: Ident = content.parse()?;
+				if keyword != "arg" {
+					return Err(Error::new_spanned(keyword, "Expected `arg = expr`"));
+				}
+				content.parse::<Token![=]>()?;
+				source = VersionSource::Arg(Box::new(content.parse()?));
+				content.parse::<Option<Token![,]>>()?;
+			}
+			Ok(Version {
+				current,
+				source,
+				migrations: vec![],
+			})
+		})
+		.parse2(attr.tokens.clone());
+
+		match parsed {
+			Ok(parsed) => {
+				if version.is_some() {
+					errors.push(
+						Error::new_spanned(attr, "Duplicate #[seed_version]").to_compile_error(),
+					);
+				}
+				version = Some(parsed);
+			}
+			Err(error) => errors.push(error.to_compile_error()),
+		}
+	}
+
+	for attr in input
+		.attrs
+		.iter()
+		.filter(|a| a.path.is_ident("seed_migrate"))
+	{
+		let parsed = (|input: ParseStream| {
+			let content;
+			parenthesized!(content in input);
+			let mut from = None;
+			let mut with = None;
+			while !content.is_empty() {
+				let key: Ident = content.parse()?;
+				content.parse::<Token![=]>()?;
+				match key.to_string().as_str() {
+					"from" if from.is_none() => {
+						from = Some(content.parse::<LitInt>()?.base10_parse::<u32>()?)
+					}
+					"with" if with.is_none() => with = Some(content.parse::<Path>()?),
+					"from" | "with" => {
+						return Err(Error::new_spanned(&key, format!("Duplicate `{}`", key)))
+					}
+					_ => {
+						return Err(Error::new_spanned(
+							key,
+							"Expected `from = n` or `with = path`",
+						))
+					}
+				}
+				content.parse::<Option<Token![,]>>()?;
+			}
+			match (from, with) {
+				(Some(from), Some(with)) => Ok(Migration { from, with }),
+				_ => Err(content.error("Expected #[seed_migrate(from = n, with = path)]")),
+			}
+		})
+		.parse2(attr.tokens.clone());
+
+		match (parsed, &mut version) {
+			(Ok(migration), Some(version)) => {
+				if migration.from >= version.current {
+					errors.push(
+						Error::new_spanned(
+							attr,
+							"Migrations must start from a version before the #[seed_version]",
+						)
+						.to_compile_error(),
+					);
+				} else if version.migrations.iter().any(|m| m.from == migration.from) {
+					errors.push(
+						Error::new_spanned(attr, "Duplicate #[seed_migrate] for this version")
+							.to_compile_error(),
+					);
+				} else {
+					version.migrations.push(migration);
+				}
+			}
+			(Ok(_), None) => errors.push(
+				Error::new_spanned(attr, "#[seed_migrate] requires #[seed_version]")
+					.to_compile_error(),
+			),
+			(Err(error), _) => errors.push(error.to_compile_error()),
+		}
+	}
+
+	if let Some(version) = &mut version {
+		version.migrations.sort_by_key(|m| m.from);
+	}
+	version
+}
+
+/// The format versions a field appears in, from its `since` and `until`.
+#[derive(Clone)]
+pub struct FieldVersions {
+	pub since: u32,
+	pub until: Option<u32>,
+}
+
+impl FieldVersions {
+	pub fn contains(&self, version: u32) -> bool {
+		version >= self.since && !matches!(self.until, Some(until) if version >= until)
+	}
+
+	/// An expression that checks the local `version` (with mixed-site hygiene).
+	/// It's spelled the way Clippy prefers, since it ends up in the user's crate.
+	pub fn condition(&self) -> TokenStream {
+		match (self.since, self.until) {
+			(0, Some(until)) => quote_spanned!(Span::mixed_site()=> (version < #until)),
+			(since, Some(until)) => {
+				quote_spanned!(Span::mixed_site()=> (#since..#until).contains(&version))
+			}
+			(0, None) => quote_spanned!(Span::mixed_site()=> true),
+			(since, None) => quote_spanned!(Span::mixed_site()=> (version >= #since)),
+		}
+	}
+}
+
+/// Validates a field's `since` and `until` against the container's `version`.
+pub fn field_versions(
+	field: &Field,
+	since: Option<u32>,
+	until: Option<u32>,
+	version: Option<&Version>,
+	errors: &mut Vec<TokenStream>,
+) -> Option<FieldVersions> {
+	if since.is_none() && until.is_none() {
+		return None;
+	}
+	let version = match version {
+		Some(version) => version,
+		None => {
+			errors.push(
+				Error::new_spanned(field, "`since` and `until` require #[seed_version]")
+					.to_compile_error(),
+			);
+			return None;
+		}
+	};
+
+	let versions = FieldVersions {
+		since: since.unwrap_or(0),
+		until,
+	};
+	if versions.since > version.current {
+		errors.push(
+			Error::new_spanned(field, "`since` is later than the #[seed_version]")
+				.to_compile_error(),
+		);
+	}
+	if matches!(versions.until, Some(until) if until <= versions.since) {
+		errors.push(
+			Error::new_spanned(field, "`until` must be later than `since`").to_compile_error(),
+		);
+	}
+	Some(versions)
+}
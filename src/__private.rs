@@ -0,0 +1,841 @@
+//! Support code for the derive macros. Not public API.
+
+use crate::content::{Content, ContentRefDeserializer};
+use serde::{
+	de,
+	ser::{self, Impossible, SerializeMap as _, SerializeStruct as _},
+};
+use std::{cell::Cell, fmt, marker::PhantomData};
+
+/// Lets an opaque return type capture `'a` without requiring it to outlive anything.
+pub trait Captures<'a> {}
+impl<'a, T: ?Sized> Captures<'a> for T {}
+
+/// Reads the tag of an internally tagged enum and buffers everything else.
+pub fn deserialize_internally_tagged<'de, T: de::Deserialize<'de>, D: de::Deserializer<'de>>(
+	deserializer: D,
+	tag: &'static str,
+	expecting: &'static str,
+) -> Result<(T, Content<'de>), D::Error> {
+	deserializer.deserialize_any(TaggedContentVisitor {
+		tag,
+		expecting,
+		phantom: PhantomData,
+	})
+}
+
+struct TaggedContentVisitor<T> {
+	tag: &'static str,
+	expecting: &'static str,
+	phantom: PhantomData<fn() -> T>,
+}
+
+impl<'de, T: de::Deserialize<'de>> de::Visitor<'de> for TaggedContentVisitor<T> {
+	type Value = (T, Content<'de>);
+
+	fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.expecting)
+	}
+
+	fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+		let tag = seq
+			.next_element()?
+			.ok_or_else(|| de::Error::missing_field(self.tag))?;
+		let mut rest = vec![];
+		while let Some(element) = seq.next_element()? {
+			rest.push(element);
+		}
+		Ok((tag, Content::Seq(rest)))
+	}
+
+	fn visit_map<A: de::MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+		let mut tag = None;
+		let mut rest = vec![];
+		while let Some(key) = map.next_key::<Content>()? {
+			if key.as_str() == Some(self.tag) {
+				if tag.is_some() {
+					return Err(de::Error::duplicate_field(self.tag));
+				}
+				tag = Some(map.next_value()?);
+			} else {
+				rest.push((key, map.next_value()?));
+			}
+		}
+		match tag {
+			Some(tag) => Ok((tag, Content::Map(rest))),
+			None => Err(de::Error::missing_field(self.tag)),
+		}
+	}
+}
+
+/// A key of an adjacently tagged enum.
+pub enum AdjacentKey {
+	Tag,
+	Content,
+	Other,
+}
+
+pub struct AdjacentKeySeed {
+	pub tag: &'static str,
+	pub content: &'static str,
+}
+
+impl<'de> de::DeserializeSeed<'de> for AdjacentKeySeed {
+	type Value = AdjacentKey;
+
+	fn deserialize<D: de::Deserializer<'de>>(
+		self,
+		deserializer: D,
+	) -> Result<AdjacentKey, D::Error> {
+		deserializer.deserialize_identifier(self)
+	}
+}
+
+impl<'de> de::Visitor<'de> for AdjacentKeySeed {
+	type Value = AdjacentKey;
+
+	fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{:?} or {:?}", self.tag, self.content)
+	}
+
+	fn visit_u64<E: de::Error>(self, v: u64) -> Result<AdjacentKey, E> {
+		Ok(match v {
+			0 => AdjacentKey::Tag,
+			1 => AdjacentKey::Content,
+			_ => AdjacentKey::Other,
+		})
+	}
+
+	fn visit_str<E: de::Error>(self, v: &str) -> Result<AdjacentKey, E> {
+		Ok(if v == self.tag {
+			AdjacentKey::Tag
+		} else if v == self.content {
+			AdjacentKey::Content
+		} else {
+			AdjacentKey::Other
+		})
+	}
+
+	fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<AdjacentKey, E> {
+		Ok(if v == self.tag.as_bytes() {
+			AdjacentKey::Tag
+		} else if v == self.content.as_bytes() {
+			AdjacentKey::Content
+		} else {
+			AdjacentKey::Other
+		})
+	}
+}
+
+/// Serializes a newtype variant's content with the tag of an internally tagged enum added to it.
+pub struct TaggedSerializer<S> {
+	pub type_ident: &'static str,
+	pub variant_ident: &'static str,
+	pub tag: &'static str,
+	pub variant_name: &'static str,
+	pub delegate: S,
+}
+
+impl<S: ser::Serializer> TaggedSerializer<S> {
+	fn bad_type(&self, what: &str) -> S::Error {
+		ser::Error::custom(format_args!(
+			"cannot serialize tagged newtype variant {}::{} containing {}",
+			self.type_ident, self.variant_ident, what,
+		))
+	}
+}
+
+impl<S: ser::Serializer> ser::Serializer for TaggedSerializer<S> {
+	type Ok = S::Ok;
+	type Error = S::Error;
+
+	type SerializeSeq = Impossible<S::Ok, S::Error>;
+	type SerializeTuple = Impossible<S::Ok, S::Error>;
+	type SerializeTupleStruct = Impossible<S::Ok, S::Error>;
+	type SerializeTupleVariant = Impossible<S::Ok, S::Error>;
+	type SerializeMap = S::SerializeMap;
+	type SerializeStruct = S::SerializeStruct;
+	type SerializeStructVariant = Impossible<S::Ok, S::Error>;
+
+	fn serialize_bool(self, _: bool) -> Result<S::Ok, S::Error> {
+		Err(self.bad_type("a boolean"))
+	}
+
+	fn serialize_i8(self, _: i8) -> Result<S::Ok, S::Error> {
+		Err(self.bad_type("an integer"))
+	}
+
+	fn serialize_i16(self, _: i16) -> Result<S::Ok, S::Error> {
+		Err(self.bad_type("an integer"))
+	}
+
+	fn serialize_i32(self, _: i32) -> Result<S::Ok, S::Error> {
+		Err(self.bad_type("an integer"))
+	}
+
+	fn serialize_i64(self, _: i64) -> Result<S::Ok, S::Error> {
+		Err(self.bad_type("an integer"))
+	}
+
+	fn serialize_u8(self, _: u8) -> Result<S::Ok, S::Error> {
+		Err(self.bad_type("an integer"))
+	}
+
+	fn serialize_u16(self, _: u16) -> Result<S::Ok, S::Error> {
+		Err(self.bad_type("an integer"))
+	}
+
+	fn serialize_u32(self, _: u32) -> Result<S::Ok, S::Error> {
+		Err(self.bad_type("an integer"))
+	}
+
+	fn serialize_u64(self, _: u64) -> Result<S::Ok, S::Error> {
+		Err(self.bad_type("an integer"))
+	}
+
+	fn serialize_f32(self, _: f32) -> Result<S::Ok, S::Error> {
+		Err(self.bad_type("a float"))
+	}
+
+	fn serialize_f64(self, _: f64) -> Result<S::Ok, S::Error> {
+		Err(self.bad_type("a float"))
+	}
+
+	fn serialize_char(self, _: char) -> Result<S::Ok, S::Error> {
+		Err(self.bad_type("a char"))
+	}
+
+	fn serialize_str(self, _: &str) -> Result<S::Ok, S::Error> {
+		Err(self.bad_type("a string"))
+	}
+
+	fn serialize_bytes(self, _: &[u8]) -> Result<S::Ok, S::Error> {
+		Err(self.bad_type("a byte array"))
+	}
+
+	fn serialize_none(self) -> Result<S::Ok, S::Error> {
+		Err(self.bad_type("an optional"))
+	}
+
+	fn serialize_some<T: ?Sized + ser::Serialize>(self, value: &T) -> Result<S::Ok, S::Error> {
+		value.serialize(self)
+	}
+
+	fn serialize_unit(self) -> Result<S::Ok, S::Error> {
+		let mut map = self.delegate.serialize_map(Some(1))?;
+		map.serialize_entry(self.tag, self.variant_name)?;
+		map.end()
+	}
+
+	fn serialize_unit_struct(self, name: &'static str) -> Result<S::Ok, S::Error> {
+		let mut state = self.delegate.serialize_struct(name, 1)?;
+		state.serialize_field(self.tag, self.variant_name)?;
+		state.end()
+	}
+
+	fn serialize_unit_variant(
+		self,
+		_: &'static str,
+		_: u32,
+		inner_variant: &'static str,
+	) -> Result<S::Ok, S::Error> {
+		let mut map = self.delegate.serialize_map(Some(2))?;
+		map.serialize_entry(self.tag, self.variant_name)?;
+		map.serialize_entry(inner_variant, &())?;
+		map.end()
+	}
+
+	fn serialize_newtype_struct<T: ?Sized + ser::Serialize>(
+		self,
+		_: &'static str,
+		value: &T,
+	) -> Result<S::Ok, S::Error> {
+		value.serialize(self)
+	}
+
+	fn serialize_newtype_variant<T: ?Sized + ser::Serialize>(
+		self,
+		_: &'static str,
+		_: u32,
+		inner_variant: &'static str,
+		inner_value: &T,
+	) -> Result<S::Ok, S::Error> {
+		let mut map = self.delegate.serialize_map(Some(2))?;
+		map.serialize_entry(self.tag, self.variant_name)?;
+		map.serialize_entry(inner_variant, inner_value)?;
+		map.end()
+	}
+
+	fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, S::Error> {
+		Err(self.bad_type("a sequence"))
+	}
+
+	fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple, S::Error> {
+		Err(self.bad_type("a tuple"))
+	}
+
+	fn serialize_tuple_struct(
+		self,
+		_: &'static str,
+		_: usize,
+	) -> Result<Self::SerializeTupleStruct, S::Error> {
+		Err(self.bad_type("a tuple struct"))
+	}
+
+	fn serialize_tuple_variant(
+		self,
+		_: &'static str,
+		_: u32,
+		_: &'static str,
+		_: usize,
+	) -> Result<Self::SerializeTupleVariant, S::Error> {
+		Err(self.bad_type("a tuple variant"))
+	}
+
+	fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, S::Error> {
+		let mut map = self.delegate.serialize_map(len.map(|len| len + 1))?;
+		map.serialize_entry(self.tag, self.variant_name)?;
+		Ok(map)
+	}
+
+	fn serialize_struct(
+		self,
+		name: &'static str,
+		len: usize,
+	) -> Result<Self::SerializeStruct, S::Error> {
+		let mut state = self.delegate.serialize_struct(name, len + 1)?;
+		state.serialize_field(self.tag, self.variant_name)?;
+		Ok(state)
+	}
+
+	fn serialize_struct_variant(
+		self,
+		_: &'static str,
+		_: u32,
+		_: &'static str,
+		_: usize,
+	) -> Result<Self::SerializeStructVariant, S::Error> {
+		Err(self.bad_type("a struct variant"))
+	}
+}
+
+/// Writes the entries of a flattened field's struct or map into the containing map.
+pub struct FlatMapSerializer<'a, M> {
+	pub type_ident: &'static str,
+	pub field_ident: &'static str,
+	pub map: &'a mut M,
+}
+
+impl<'a, M: ser::SerializeMap> FlatMapSerializer<'a, M> {
+	fn bad_type(&self, what: &str) -> M::Error {
+		ser::Error::custom(format_args!(
+			"cannot flatten field {}::{} containing {}",
+			self.type_ident, self.field_ident, what,
+		))
+	}
+}
+
+impl<'a, M: ser::SerializeMap> ser::Serializer for FlatMapSerializer<'a, M> {
+	type Ok = ();
+	type Error = M::Error;
+
+	type SerializeSeq = Impossible<(), M::Error>;
+	type SerializeTuple = Impossible<(), M::Error>;
+	type SerializeTupleStruct = Impossible<(), M::Error>;
+	type SerializeTupleVariant = Impossible<(), M::Error>;
+	type SerializeMap = FlatMapSerializeMap<'a, M>;
+	type SerializeStruct = FlatMapSerializeMap<'a, M>;
+	type SerializeStructVariant = Impossible<(), M::Error>;
+
+	fn serialize_bool(self, _: bool) -> Result<(), M::Error> {
+		Err(self.bad_type("a boolean"))
+	}
+
+	fn serialize_i8(self, _: i8) -> Result<(), M::Error> {
+		Err(self.bad_type("an integer"))
+	}
+
+	fn serialize_i16(self, _: i16) -> Result<(), M::Error> {
+		Err(self.bad_type("an integer"))
+	}
+
+	fn serialize_i32(self, _: i32) -> Result<(), M::Error> {
+		Err(self.bad_type("an integer"))
+	}
+
+	fn serialize_i64(self, _: i64) -> Result<(), M::Error> {
+		Err(self.bad_type("an integer"))
+	}
+
+	fn serialize_u8(self, _: u8) -> Result<(), M::Error> {
+		Err(self.bad_type("an integer"))
+	}
+
+	fn serialize_u16(self, _: u16) -> Result<(), M::Error> {
+		Err(self.bad_type("an integer"))
+	}
+
+	fn serialize_u32(self, _: u32) -> Result<(), M::Error> {
+		Err(self.bad_type("an integer"))
+	}
+
+	fn serialize_u64(self, _: u64) -> Result<(), M::Error> {
+		Err(self.bad_type("an integer"))
+	}
+
+	fn serialize_f32(self, _: f32) -> Result<(), M::Error> {
+		Err(self.bad_type("a float"))
+	}
+
+	fn serialize_f64(self, _: f64) -> Result<(), M::Error> {
+		Err(self.bad_type("a float"))
+	}
+
+	fn serialize_char(self, _: char) -> Result<(), M::Error> {
+		Err(self.bad_type("a char"))
+	}
+
+	fn serialize_str(self, _: &str) -> Result<(), M::Error> {
+		Err(self.bad_type("a string"))
+	}
+
+	fn serialize_bytes(self, _: &[u8]) -> Result<(), M::Error> {
+		Err(self.bad_type("a byte array"))
+	}
+
+	// A missing optional contributes no entries.
+	fn serialize_none(self) -> Result<(), M::Error> {
+		Ok(())
+	}
+
+	fn serialize_some<T: ?Sized + ser::Serialize>(self, value: &T) -> Result<(), M::Error> {
+		value.serialize(self)
+	}
+
+	fn serialize_unit(self) -> Result<(), M::Error> {
+		Ok(())
+	}
+
+	fn serialize_unit_struct(self, _: &'static str) -> Result<(), M::Error> {
+		Ok(())
+	}
+
+	fn serialize_unit_variant(
+		self,
+		_: &'static str,
+		_: u32,
+		_: &'static str,
+	) -> Result<(), M::Error> {
+		Err(self.bad_type("an enum"))
+	}
+
+	fn serialize_newtype_struct<T: ?Sized + ser::Serialize>(
+		self,
+		_: &'static str,
+		value: &T,
+	) -> Result<(), M::Error> {
+		value.serialize(self)
+	}
+
+	fn serialize_newtype_variant<T: ?Sized + ser::Serialize>(
+		self,
+		_: &'static str,
+		_: u32,
+		_: &'static str,
+		_: &T,
+	) -> Result<(), M::Error> {
+		Err(self.bad_type("an enum"))
+	}
+
+	fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, M::Error> {
+		Err(self.bad_type("a sequence"))
+	}
+
+	fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple, M::Error> {
+		Err(self.bad_type("a tuple"))
+	}
+
+	fn serialize_tuple_struct(
+		self,
+		_: &'static str,
+		_: usize,
+	) -> Result<Self::SerializeTupleStruct, M::Error> {
+		Err(self.bad_type("a tuple struct"))
+	}
+
+	fn serialize_tuple_variant(
+		self,
+		_: &'static str,
+		_: u32,
+		_: &'static str,
+		_: usize,
+	) -> Result<Self::SerializeTupleVariant, M::Error> {
+		Err(self.bad_type("an enum"))
+	}
+
+	fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, M::Error> {
+		Ok(FlatMapSerializeMap(self.map))
+	}
+
+	fn serialize_struct(
+		self,
+		_: &'static str,
+		_: usize,
+	) -> Result<Self::SerializeStruct, M::Error> {
+		Ok(FlatMapSerializeMap(self.map))
+	}
+
+	fn serialize_struct_variant(
+		self,
+		_: &'static str,
+		_: u32,
+		_: &'static str,
+		_: usize,
+	) -> Result<Self::SerializeStructVariant, M::Error> {
+		Err(self.bad_type("an enum"))
+	}
+}
+
+pub struct FlatMapSerializeMap<'a, M>(&'a mut M);
+
+impl<'a, M: ser::SerializeMap> ser::SerializeMap for FlatMapSerializeMap<'a, M> {
+	type Ok = ();
+	type Error = M::Error;
+
+	fn serialize_key<T: ?Sized + ser::Serialize>(&mut self, key: &T) -> Result<(), M::Error> {
+		self.0.serialize_key(key)
+	}
+
+	fn serialize_value<T: ?Sized + ser::Serialize>(&mut self, value: &T) -> Result<(), M::Error> {
+		self.0.serialize_value(value)
+	}
+
+	fn serialize_entry<K: ?Sized + ser::Serialize, V: ?Sized + ser::Serialize>(
+		&mut self,
+		key: &K,
+		value: &V,
+	) -> Result<(), M::Error> {
+		self.0.serialize_entry(key, value)
+	}
+
+	fn end(self) -> Result<(), M::Error> {
+		Ok(())
+	}
+}
+
+impl<'a, M: ser::SerializeMap> ser::SerializeStruct for FlatMapSerializeMap<'a, M> {
+	type Ok = ();
+	type Error = M::Error;
+
+	fn serialize_field<T: ?Sized + ser::Serialize>(
+		&mut self,
+		key: &'static str,
+		value: &T,
+	) -> Result<(), M::Error> {
+		self.0.serialize_entry(key, value)
+	}
+
+	fn end(self) -> Result<(), M::Error> {
+		Ok(())
+	}
+}
+
+/// The map entries buffered for flattened fields.
+///
+/// Entries are taken out as a flattened field consumes them, so that only unclaimed ones remain afterwards.
+pub type FlatEntries<'de> = Vec<Option<(Content<'de>, Content<'de>)>>;
+
+/// Deserializes a flattened field from the entries its containing struct didn't recognise.
+///
+/// Maps claim all entries that are left. Structs are offered them all too, but only claim those whose values they read
+/// rather than ignore, so that they also find their fields under aliases that aren't among their `fields`.
+pub struct FlatMapDeserializer<'a, 'de, E> {
+	entries: &'a mut FlatEntries<'de>,
+	err: PhantomData<fn() -> E>,
+}
+
+impl<'a, 'de, E> FlatMapDeserializer<'a, 'de, E> {
+	pub fn new(entries: &'a mut FlatEntries<'de>) -> Self {
+		Self {
+			entries,
+			err: PhantomData,
+		}
+	}
+}
+
+impl<'a, 'de, E: de::Error> de::Deserializer<'de> for FlatMapDeserializer<'a, 'de, E> {
+	type Error = E;
+
+	fn deserialize_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, E> {
+		self.deserialize_map(visitor)
+	}
+
+	fn deserialize_map<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, E> {
+		visitor.visit_map(FlatMapAccess {
+			entries: self.entries.iter_mut(),
+			claim_ignored: true,
+			entry: None,
+			err: PhantomData,
+		})
+	}
+
+	fn deserialize_struct<V: de::Visitor<'de>>(
+		self,
+		_: &'static str,
+		_: &'static [&'static str],
+		visitor: V,
+	) -> Result<V::Value, E> {
+		visitor.visit_map(FlatMapAccess {
+			entries: self.entries.iter_mut(),
+			claim_ignored: false,
+			entry: None,
+			err: PhantomData,
+		})
+	}
+
+	fn deserialize_option<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, E> {
+		visitor.visit_some(self)
+	}
+
+	fn deserialize_newtype_struct<V: de::Visitor<'de>>(
+		self,
+		_: &'static str,
+		visitor: V,
+	) -> Result<V::Value, E> {
+		visitor.visit_newtype_struct(self)
+	}
+
+	fn deserialize_unit<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, E> {
+		visitor.visit_unit()
+	}
+
+	fn deserialize_unit_struct<V: de::Visitor<'de>>(
+		self,
+		_: &'static str,
+		visitor: V,
+	) -> Result<V::Value, E> {
+		visitor.visit_unit()
+	}
+
+	fn deserialize_ignored_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, E> {
+		visitor.visit_unit()
+	}
+
+	serde::forward_to_deserialize_any! {
+		bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+		bytes byte_buf seq tuple tuple_struct enum identifier
+	}
+}
+
+struct FlatMapAccess<'a, 'de, E> {
+	entries: std::slice::IterMut<'a, Option<(Content<'de>, Content<'de>)>>,
+	/// Whether entries are claimed even if their values are ignored.
+	claim_ignored: bool,
+	/// The entry whose key was read last.
+	entry: Option<&'a mut Option<(Content<'de>, Content<'de>)>>,
+	err: PhantomData<fn() -> E>,
+}
+
+impl<'a, 'de, E: de::Error> de::MapAccess<'de> for FlatMapAccess<'a, 'de, E> {
+	type Error = E;
+
+	fn next_key_seed<K: de::DeserializeSeed<'de>>(
+		&mut self,
+		seed: K,
+	) -> Result<Option<K::Value>, E> {
+		match self.entries.find(|entry| entry.is_some()) {
+			Some(entry) => {
+				let key = match &*entry {
+					Some((key, _)) => seed.deserialize(ContentRefDeserializer::<E>::new(key))?,
+					None => unreachable!(),
+				};
+				self.entry = Some(entry);
+				Ok(Some(key))
+			}
+			None => Ok(None),
+		}
+	}
+
+	fn next_value_seed<V: de::DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, E> {
+		let entry = match self.entry.take() {
+			Some(entry) => entry,
+			None => return Err(de::Error::custom("value is missing")),
+		};
+		let ignored = Cell::new(false);
+		let value = match &*entry {
+			Some((_, value)) => seed.deserialize(UnlessIgnored {
+				content: ContentRefDeserializer::<E>::new(value),
+				ignored: &ignored,
+			})?,
+			None => unreachable!(),
+		};
+		if self.claim_ignored || !ignored.get() {
+			*entry = None;
+		}
+		Ok(value)
+	}
+}
+
+/// Deserializes like the [`ContentRefDeserializer`] it wraps, but notes whether the value was ignored.
+struct UnlessIgnored<'a, 'de, E> {
+	content: ContentRefDeserializer<'a, 'de, E>,
+	ignored: &'a Cell<bool>,
+}
+
+impl<'a, 'de, E: de::Error> de::Deserializer<'de> for UnlessIgnored<'a, 'de, E> {
+	type Error = E;
+
+	fn deserialize_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, E> {
+		self.content.deserialize_any(visitor)
+	}
+
+	fn deserialize_option<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, E> {
+		self.content.deserialize_option(visitor)
+	}
+
+	fn deserialize_unit<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, E> {
+		self.content.deserialize_unit(visitor)
+	}
+
+	fn deserialize_unit_struct<V: de::Visitor<'de>>(
+		self,
+		name: &'static str,
+		visitor: V,
+	) -> Result<V::Value, E> {
+		self.content.deserialize_unit_struct(name, visitor)
+	}
+
+	fn deserialize_newtype_struct<V: de::Visitor<'de>>(
+		self,
+		name: &'static str,
+		visitor: V,
+	) -> Result<V::Value, E> {
+		self.content.deserialize_newtype_struct(name, visitor)
+	}
+
+	fn deserialize_enum<V: de::Visitor<'de>>(
+		self,
+		name: &'static str,
+		variants: &'static [&'static str],
+		visitor: V,
+	) -> Result<V::Value, E> {
+		self.content.deserialize_enum(name, variants, visitor)
+	}
+
+	fn deserialize_ignored_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, E> {
+		self.ignored.set(true);
+		self.content.deserialize_ignored_any(visitor)
+	}
+
+	serde::forward_to_deserialize_any! {
+		bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+		bytes byte_buf seq tuple tuple_struct map struct identifier
+	}
+}
+
+/// Reads a version number and then the value in that version, as 2-tuple.
+pub struct VersionedSeed<F> {
+	current: u32,
+	seed: F,
+}
+
+impl<F> VersionedSeed<F> {
+	/// `seed` receives the version, which is at most `current`.
+	///
+	/// The bound here lets `seed` be inferred as [`FnOnce`], so that it can hand out what it captured.
+	pub fn new<'de, S: de::DeserializeSeed<'de>>(current: u32, seed: F) -> Self
+	where
+		F: FnOnce(u32) -> S,
+	{
+		Self { current, seed }
+	}
+}
+
+impl<'de, F: FnOnce(u32) -> S, S: de::DeserializeSeed<'de>> de::DeserializeSeed<'de>
+	for VersionedSeed<F>
+{
+	type Value = (u32, S::Value);
+
+	fn deserialize<D: de::Deserializer<'de>>(
+		self,
+		deserializer: D,
+	) -> Result<Self::Value, D::Error> {
+		deserializer.deserialize_tuple(2, self)
+	}
+}
+
+impl<'de, F: FnOnce(u32) -> S, S: de::DeserializeSeed<'de>> de::Visitor<'de> for VersionedSeed<F> {
+	type Value = (u32, S::Value);
+
+	fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "a version up to {} followed by the value", self.current)
+	}
+
+	fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+		let version: u32 = seq
+			.next_element()?
+			.ok_or_else(|| de::Error::invalid_length(0, &self))?;
+		if version > self.current {
+			return Err(de::Error::invalid_value(
+				de::Unexpected::Unsigned(version.into()),
+				&format!("a version up to {}", self.current).as_str(),
+			));
+		}
+		let current = self.current;
+		let value = seq
+			.next_element_seed((self.seed)(version))?
+			.ok_or_else(|| {
+				de::Error::invalid_length(
+					1,
+					&format!("a version up to {} followed by the value", current).as_str(),
+				)
+			})?;
+		Ok((version, value))
+	}
+}
+
+/// Gives each attempted variant of an untagged enum its own copy of an owned seed argument,
+/// which is why those arguments must be [`Clone`].
+pub fn clone_for_untagged_variant<T: Clone>(arg: &T) -> T {
+	arg.clone()
+}
+
+/// Fails if a version passed in as seed argument is newer than `current`.
+pub fn check_version<E: de::Error>(version: u32, current: u32) -> Result<u32, E> {
+	if version > current {
+		Err(de::Error::custom(format_args!(
+			"unsupported version {}, expected a version up to {}",
+			version, current
+		)))
+	} else {
+		Ok(version)
+	}
+}
+
+/// Implemented by derived serializable views with a version header, to write their value after it.
+pub trait SerializeBody {
+	fn serialize_body<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>;
+}
+
+/// Serializes a [`SerializeBody`]'s value.
+pub struct Body<'a, T: ?Sized>(pub &'a T);
+
+impl<'a, T: ?Sized + SerializeBody> ser::Serialize for Body<'a, T> {
+	fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		self.0.serialize_body(serializer)
+	}
+}
+
+/// Serializes `version` followed by the value, as 2-tuple.
+pub fn serialize_versioned<T: ?Sized + SerializeBody, S: ser::Serializer>(
+	version: u32,
+	value: &T,
+	serializer: S,
+) -> Result<S::Ok, S::Error> {
+	use ser::SerializeTuple as _;
+
+	let mut tuple = serializer.serialize_tuple(2)?;
+	tuple.serialize_element(&version)?;
+	tuple.serialize_element(&Body(value))?;
+	tuple.end()
+}
@@ -0,0 +1,571 @@
+//! A buffering layer for self-describing input.
+//!
+//! [`Content`] captures any value a self-describing [`Deserializer`](de::Deserializer) produces
+//! and [`ContentRefDeserializer`] replays it, so a [`DeserializeSeed`](de::DeserializeSeed) can run
+//! on input that had to be read ahead, for example to find an enum's tag.
+
+use serde::de::{self, Unexpected};
+use std::{fmt, marker::PhantomData};
+
+/// A buffered self-describing value.
+///
+/// Strings and byte slices are borrowed from the input where the [`Deserializer`](de::Deserializer) allows it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Content<'de> {
+	Bool(bool),
+
+	U8(u8),
+	U16(u16),
+	U32(u32),
+	U64(u64),
+
+	I8(i8),
+	I16(i16),
+	I32(i32),
+	I64(i64),
+
+	F32(f32),
+	F64(f64),
+
+	Char(char),
+	String(String),
+	Str(&'de str),
+	ByteBuf(Vec<u8>),
+	Bytes(&'de [u8]),
+
+	None,
+	Some(Box<Content<'de>>),
+
+	Unit,
+	Newtype(Box<Content<'de>>),
+	Seq(Vec<Content<'de>>),
+	Map(Vec<(Content<'de>, Content<'de>)>),
+}
+
+impl<'de> Content<'de> {
+	/// The string this [`Content`] holds, if any.
+	pub fn as_str(&self) -> Option<&str> {
+		match *self {
+			Content::Str(s) => Some(s),
+			Content::String(ref s) => Some(s),
+			Content::Bytes(b) => std::str::from_utf8(b).ok(),
+			Content::ByteBuf(ref b) => std::str::from_utf8(b).ok(),
+			_ => None,
+		}
+	}
+
+	fn unexpected(&self) -> Unexpected<'_> {
+		match *self {
+			Content::Bool(b) => Unexpected::Bool(b),
+			Content::U8(n) => Unexpected::Unsigned(n as u64),
+			Content::U16(n) => Unexpected::Unsigned(n as u64),
+			Content::U32(n) => Unexpected::Unsigned(n as u64),
+			Content::U64(n) => Unexpected::Unsigned(n),
+			Content::I8(n) => Unexpected::Signed(n as i64),
+			Content::I16(n) => Unexpected::Signed(n as i64),
+			Content::I32(n) => Unexpected::Signed(n as i64),
+			Content::I64(n) => Unexpected::Signed(n),
+			Content::F32(f) => Unexpected::Float(f as f64),
+			Content::F64(f) => Unexpected::Float(f),
+			Content::Char(c) => Unexpected::Char(c),
+			Content::String(ref s) => Unexpected::Str(s),
+			Content::Str(s) => Unexpected::Str(s),
+			Content::ByteBuf(ref b) => Unexpected::Bytes(b),
+			Content::Bytes(b) => Unexpected::Bytes(b),
+			Content::None | Content::Some(_) => Unexpected::Option,
+			Content::Unit => Unexpected::Unit,
+			Content::Newtype(_) => Unexpected::NewtypeStruct,
+			Content::Seq(_) => Unexpected::Seq,
+			Content::Map(_) => Unexpected::Map,
+		}
+	}
+}
+
+impl<'de> de::Deserialize<'de> for Content<'de> {
+	fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		deserializer.deserialize_any(ContentVisitor)
+	}
+}
+
+struct ContentVisitor;
+impl<'de> de::Visitor<'de> for ContentVisitor {
+	type Value = Content<'de>;
+
+	fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("any value")
+	}
+
+	fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+		Ok(Content::Bool(v))
+	}
+
+	fn visit_i8<E: de::Error>(self, v: i8) -> Result<Self::Value, E> {
+		Ok(Content::I8(v))
+	}
+
+	fn visit_i16<E: de::Error>(self, v: i16) -> Result<Self::Value, E> {
+		Ok(Content::I16(v))
+	}
+
+	fn visit_i32<E: de::Error>(self, v: i32) -> Result<Self::Value, E> {
+		Ok(Content::I32(v))
+	}
+
+	fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+		Ok(Content::I64(v))
+	}
+
+	fn visit_u8<E: de::Error>(self, v: u8) -> Result<Self::Value, E> {
+		Ok(Content::U8(v))
+	}
+
+	fn visit_u16<E: de::Error>(self, v: u16) -> Result<Self::Value, E> {
+		Ok(Content::U16(v))
+	}
+
+	fn visit_u32<E: de::Error>(self, v: u32) -> Result<Self::Value, E> {
+		Ok(Content::U32(v))
+	}
+
+	fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+		Ok(Content::U64(v))
+	}
+
+	fn visit_f32<E: de::Error>(self, v: f32) -> Result<Self::Value, E> {
+		Ok(Content::F32(v))
+	}
+
+	fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+		Ok(Content::F64(v))
+	}
+
+	fn visit_char<E: de::Error>(self, v: char) -> Result<Self::Value, E> {
+		Ok(Content::Char(v))
+	}
+
+	fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+		Ok(Content::String(v.to_owned()))
+	}
+
+	fn visit_borrowed_str<E: de::Error>(self, v: &'de str) -> Result<Self::Value, E> {
+		Ok(Content::Str(v))
+	}
+
+	fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
+		Ok(Content::String(v))
+	}
+
+	fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+		Ok(Content::ByteBuf(v.to_owned()))
+	}
+
+	fn visit_borrowed_bytes<E: de::Error>(self, v: &'de [u8]) -> Result<Self::Value, E> {
+		Ok(Content::Bytes(v))
+	}
+
+	fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+		Ok(Content::ByteBuf(v))
+	}
+
+	fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+		Ok(Content::None)
+	}
+
+	fn visit_some<D: de::Deserializer<'de>>(
+		self,
+		deserializer: D,
+	) -> Result<Self::Value, D::Error> {
+		de::Deserialize::deserialize(deserializer).map(|v| Content::Some(Box::new(v)))
+	}
+
+	fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+		Ok(Content::Unit)
+	}
+
+	fn visit_newtype_struct<D: de::Deserializer<'de>>(
+		self,
+		deserializer: D,
+	) -> Result<Self::Value, D::Error> {
+		de::Deserialize::deserialize(deserializer).map(|v| Content::Newtype(Box::new(v)))
+	}
+
+	fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+		let mut vec = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+		while let Some(element) = seq.next_element()? {
+			vec.push(element);
+		}
+		Ok(Content::Seq(vec))
+	}
+
+	fn visit_map<A: de::MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+		let mut vec = Vec::with_capacity(map.size_hint().unwrap_or(0).min(4096));
+		while let Some(entry) = map.next_entry()? {
+			vec.push(entry);
+		}
+		Ok(Content::Map(vec))
+	}
+
+	fn visit_enum<A: de::EnumAccess<'de>>(self, _data: A) -> Result<Self::Value, A::Error> {
+		Err(de::Error::custom(
+			"enum input can't be buffered, since its representation depends on the format",
+		))
+	}
+}
+
+/// A [`Deserializer`](de::Deserializer) that replays a borrowed [`Content`].
+///
+/// Since the [`Content`] isn't consumed, it can be replayed any number of times.
+pub struct ContentRefDeserializer<'a, 'de, E> {
+	content: &'a Content<'de>,
+	err: PhantomData<E>,
+}
+
+impl<'a, 'de, E> ContentRefDeserializer<'a, 'de, E> {
+	pub fn new(content: &'a Content<'de>) -> Self {
+		Self {
+			content,
+			err: PhantomData,
+		}
+	}
+}
+
+impl<'a, 'de, E> Clone for ContentRefDeserializer<'a, 'de, E> {
+	fn clone(&self) -> Self {
+		*self
+	}
+}
+impl<'a, 'de, E> Copy for ContentRefDeserializer<'a, 'de, E> {}
+
+impl<'a, 'de, E: de::Error> ContentRefDeserializer<'a, 'de, E> {
+	fn invalid_type(self, exp: &dyn de::Expected) -> E {
+		de::Error::invalid_type(self.content.unexpected(), exp)
+	}
+}
+
+fn visit_content_seq<'a, 'de, V: de::Visitor<'de>, E: de::Error>(
+	content: &'a [Content<'de>],
+	visitor: V,
+) -> Result<V::Value, E> {
+	let mut seq = SeqRefDeserializer {
+		iter: content.iter(),
+		count: 0,
+		err: PhantomData,
+	};
+	let value = visitor.visit_seq(&mut seq)?;
+	let remaining = seq.iter.len();
+	if remaining == 0 {
+		Ok(value)
+	} else {
+		Err(de::Error::invalid_length(
+			seq.count + remaining,
+			&ExpectedInSeq(seq.count),
+		))
+	}
+}
+
+fn visit_content_map<'a, 'de, V: de::Visitor<'de>, E: de::Error>(
+	content: &'a [(Content<'de>, Content<'de>)],
+	visitor: V,
+) -> Result<V::Value, E> {
+	let mut map = MapRefDeserializer {
+		iter: content.iter(),
+		value: None,
+		count: 0,
+		err: PhantomData,
+	};
+	let value = visitor.visit_map(&mut map)?;
+	let remaining = map.iter.len();
+	if remaining == 0 {
+		Ok(value)
+	} else {
+		Err(de::Error::invalid_length(
+			map.count + remaining,
+			&ExpectedInMap(map.count),
+		))
+	}
+}
+
+impl<'a, 'de, E: de::Error> de::Deserializer<'de> for ContentRefDeserializer<'a, 'de, E> {
+	type Error = E;
+
+	fn deserialize_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, E> {
+		match *self.content {
+			Content::Bool(v) => visitor.visit_bool(v),
+			Content::U8(v) => visitor.visit_u8(v),
+			Content::U16(v) => visitor.visit_u16(v),
+			Content::U32(v) => visitor.visit_u32(v),
+			Content::U64(v) => visitor.visit_u64(v),
+			Content::I8(v) => visitor.visit_i8(v),
+			Content::I16(v) => visitor.visit_i16(v),
+			Content::I32(v) => visitor.visit_i32(v),
+			Content::I64(v) => visitor.visit_i64(v),
+			Content::F32(v) => visitor.visit_f32(v),
+			Content::F64(v) => visitor.visit_f64(v),
+			Content::Char(v) => visitor.visit_char(v),
+			Content::String(ref v) => visitor.visit_str(v),
+			Content::Str(v) => visitor.visit_borrowed_str(v),
+			Content::ByteBuf(ref v) => visitor.visit_bytes(v),
+			Content::Bytes(v) => visitor.visit_borrowed_bytes(v),
+			Content::None => visitor.visit_none(),
+			Content::Some(ref v) => visitor.visit_some(ContentRefDeserializer::new(v)),
+			Content::Unit => visitor.visit_unit(),
+			Content::Newtype(ref v) => visitor.visit_newtype_struct(ContentRefDeserializer::new(v)),
+			Content::Seq(ref v) => visit_content_seq(v, visitor),
+			Content::Map(ref v) => visit_content_map(v, visitor),
+		}
+	}
+
+	fn deserialize_option<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, E> {
+		match *self.content {
+			Content::None | Content::Unit => visitor.visit_none(),
+			Content::Some(ref v) => visitor.visit_some(ContentRefDeserializer::new(v)),
+			_ => visitor.visit_some(self),
+		}
+	}
+
+	fn deserialize_unit<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, E> {
+		match *self.content {
+			Content::Unit => visitor.visit_unit(),
+			_ => Err(self.invalid_type(&visitor)),
+		}
+	}
+
+	fn deserialize_unit_struct<V: de::Visitor<'de>>(
+		self,
+		_name: &'static str,
+		visitor: V,
+	) -> Result<V::Value, E> {
+		match *self.content {
+			// Some formats represent unit structs as empty sequences or maps.
+			Content::Seq(ref v) if v.is_empty() => visitor.visit_unit(),
+			Content::Map(ref v) if v.is_empty() => visitor.visit_unit(),
+			_ => self.deserialize_unit(visitor),
+		}
+	}
+
+	fn deserialize_newtype_struct<V: de::Visitor<'de>>(
+		self,
+		_name: &'static str,
+		visitor: V,
+	) -> Result<V::Value, E> {
+		match *self.content {
+			Content::Newtype(ref v) => visitor.visit_newtype_struct(ContentRefDeserializer::new(v)),
+			_ => visitor.visit_newtype_struct(self),
+		}
+	}
+
+	fn deserialize_enum<V: de::Visitor<'de>>(
+		self,
+		_name: &'static str,
+		_variants: &'static [&'static str],
+		visitor: V,
+	) -> Result<V::Value, E> {
+		let (variant, value) = match *self.content {
+			Content::Map(ref entries) => match entries.as_slice() {
+				[(variant, value)] => (variant, Some(value)),
+				_ => {
+					return Err(de::Error::invalid_value(
+						Unexpected::Map,
+						&"map with a single key",
+					))
+				}
+			},
+			ref s @ Content::String(_) | ref s @ Content::Str(_) => (s, None),
+			_ => {
+				return Err(de::Error::invalid_type(
+					self.content.unexpected(),
+					&"string or map",
+				))
+			}
+		};
+		visitor.visit_enum(EnumRefDeserializer {
+			variant,
+			value,
+			err: PhantomData,
+		})
+	}
+
+	fn deserialize_ignored_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, E> {
+		visitor.visit_unit()
+	}
+
+	serde::forward_to_deserialize_any! {
+		bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+		bytes byte_buf seq tuple tuple_struct map struct identifier
+	}
+}
+
+impl<'a, 'de, E: de::Error> de::IntoDeserializer<'de, E> for ContentRefDeserializer<'a, 'de, E> {
+	type Deserializer = Self;
+	fn into_deserializer(self) -> Self {
+		self
+	}
+}
+
+struct SeqRefDeserializer<'a, 'de, E> {
+	iter: std::slice::Iter<'a, Content<'de>>,
+	count: usize,
+	err: PhantomData<E>,
+}
+
+impl<'a, 'de, E: de::Error> de::SeqAccess<'de> for SeqRefDeserializer<'a, 'de, E> {
+	type Error = E;
+
+	fn next_element_seed<T: de::DeserializeSeed<'de>>(
+		&mut self,
+		seed: T,
+	) -> Result<Option<T::Value>, E> {
+		match self.iter.next() {
+			Some(content) => {
+				self.count += 1;
+				seed.deserialize(ContentRefDeserializer::new(content))
+					.map(Some)
+			}
+			None => Ok(None),
+		}
+	}
+
+	fn size_hint(&self) -> Option<usize> {
+		Some(self.iter.len())
+	}
+}
+
+struct MapRefDeserializer<'a, 'de, E> {
+	iter: std::slice::Iter<'a, (Content<'de>, Content<'de>)>,
+	value: Option<&'a Content<'de>>,
+	count: usize,
+	err: PhantomData<E>,
+}
+
+impl<'a, 'de, E: de::Error> de::MapAccess<'de> for MapRefDeserializer<'a, 'de, E> {
+	type Error = E;
+
+	fn next_key_seed<T: de::DeserializeSeed<'de>>(
+		&mut self,
+		seed: T,
+	) -> Result<Option<T::Value>, E> {
+		match self.iter.next() {
+			Some((key, value)) => {
+				self.count += 1;
+				self.value = Some(value);
+				seed.deserialize(ContentRefDeserializer::new(key)).map(Some)
+			}
+			None => Ok(None),
+		}
+	}
+
+	fn next_value_seed<T: de::DeserializeSeed<'de>>(&mut self, seed: T) -> Result<T::Value, E> {
+		match self.value.take() {
+			Some(value) => seed.deserialize(ContentRefDeserializer::new(value)),
+			None => Err(de::Error::custom("value is missing")),
+		}
+	}
+
+	fn size_hint(&self) -> Option<usize> {
+		Some(self.iter.len())
+	}
+}
+
+struct EnumRefDeserializer<'a, 'de, E> {
+	variant: &'a Content<'de>,
+	value: Option<&'a Content<'de>>,
+	err: PhantomData<E>,
+}
+
+impl<'a, 'de, E: de::Error> de::EnumAccess<'de> for EnumRefDeserializer<'a, 'de, E> {
+	type Error = E;
+	type Variant = VariantRefDeserializer<'a, 'de, E>;
+
+	fn variant_seed<V: de::DeserializeSeed<'de>>(
+		self,
+		seed: V,
+	) -> Result<(V::Value, Self::Variant), E> {
+		let variant = seed.deserialize(ContentRefDeserializer::new(self.variant))?;
+		Ok((
+			variant,
+			VariantRefDeserializer {
+				value: self.value,
+				err: PhantomData,
+			},
+		))
+	}
+}
+
+struct VariantRefDeserializer<'a, 'de, E> {
+	value: Option<&'a Content<'de>>,
+	err: PhantomData<E>,
+}
+
+impl<'a, 'de, E: de::Error> de::VariantAccess<'de> for VariantRefDeserializer<'a, 'de, E> {
+	type Error = E;
+
+	fn unit_variant(self) -> Result<(), E> {
+		match self.value {
+			None | Some(Content::Unit) => Ok(()),
+			Some(other) => Err(de::Error::invalid_type(other.unexpected(), &"unit variant")),
+		}
+	}
+
+	fn newtype_variant_seed<T: de::DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, E> {
+		match self.value {
+			Some(value) => seed.deserialize(ContentRefDeserializer::new(value)),
+			None => Err(de::Error::invalid_type(
+				Unexpected::UnitVariant,
+				&"newtype variant",
+			)),
+		}
+	}
+
+	fn tuple_variant<V: de::Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, E> {
+		match self.value {
+			Some(Content::Seq(v)) => visit_content_seq(v, visitor),
+			Some(other) => Err(de::Error::invalid_type(
+				other.unexpected(),
+				&"tuple variant",
+			)),
+			None => Err(de::Error::invalid_type(
+				Unexpected::UnitVariant,
+				&"tuple variant",
+			)),
+		}
+	}
+
+	fn struct_variant<V: de::Visitor<'de>>(
+		self,
+		_fields: &'static [&'static str],
+		visitor: V,
+	) -> Result<V::Value, E> {
+		match self.value {
+			Some(Content::Map(v)) => visit_content_map(v, visitor),
+			Some(Content::Seq(v)) => visit_content_seq(v, visitor),
+			Some(other) => Err(de::Error::invalid_type(
+				other.unexpected(),
+				&"struct variant",
+			)),
+			None => Err(de::Error::invalid_type(
+				Unexpected::UnitVariant,
+				&"struct variant",
+			)),
+		}
+	}
+}
+
+struct ExpectedInSeq(usize);
+impl de::Expected for ExpectedInSeq {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self.0 {
+			1 => f.write_str("1 element in sequence"),
+			n => write!(f, "{} elements in sequence", n),
+		}
+	}
+}
+
+struct ExpectedInMap(usize);
+impl de::Expected for ExpectedInMap {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self.0 {
+			1 => f.write_str("1 element in map"),
+			n => write!(f, "{} elements in map", n),
+		}
+	}
+}
@@ -1,7 +1,13 @@
 pub use serde_seeded_proc_macro_definitions::*;
 
+pub mod content;
+
+#[doc(hidden)]
+pub mod __private;
+
 use erased_serde as eser;
-use serde::de;
+use serde::{de, ser::SerializeTuple as _, Serialize};
+use std::{borrow::Cow, convert::TryInto as _, fmt, marker::PhantomData};
 
 pub trait DeSeeder<'de, T> {
 	type Seed: de::DeserializeSeed<'de, Value = T>;
@@ -14,6 +20,30 @@ pub trait SerSeeder<T> {
 }
 pub type Seeded<'s> = Box<dyn 's + eser::Serialize>;
 
+/// Types that can be deserialized with the arguments `Args`, as implemented by `#[derive(seed)]`
+/// with the `#[seed_args]` as tuple.
+///
+/// Types with `#[seed_profile]`s implement it once for each profile, with that profile's arguments after the
+/// `#[seed_args]`, so that the arguments pick the profile. Profiles whose argument types match another's apart from
+/// lifetimes are left out, since their implementations would overlap. Their methods can still be called directly,
+/// but `#[seeded(forward)]` can't reach them.
+///
+/// The argument types are compared as written, so profiles whose types only differ in spelling, like through a type
+/// alias or another path to the same type, aren't left out and fail to compile with conflicting implementations.
+pub trait SeedWith<'de, Args>: Sized {
+	type Seed: de::DeserializeSeed<'de, Value = Self>;
+	fn seed_with(args: Args) -> Self::Seed;
+}
+
+/// Types that can be serialized with the arguments `Args`, as implemented by `#[derive(seeded)]`
+/// with the `#[seed_args]` as tuple.
+///
+/// Like [`SeedWith`], this is implemented once for each `#[seed_profile]` with argument types of its own.
+pub trait SeededWith<'ser, Args> {
+	type Seeded: Serialize;
+	fn seeded_with(&'ser self, args: Args) -> Self::Seeded;
+}
+
 impl<S: SerSeeder<T>, T> SerSeeder<T> for &S {
 	fn seeded<'s>(&'s self, value: &'s T) -> Seeded<'s> {
 		S::seeded(self, value)
@@ -50,3 +80,204 @@ impl<F: for<'a> Fn(&'a T) -> Seeded<'a>, T> SerSeeder<T> for FnSerSeeder<F> {
 		self.0(value)
 	}
 }
+
+/// Deserializes [`Cow<str>`](Cow) and [`Cow<[u8]>`](Cow) fields by borrowing from the input where possible,
+/// rather than always copying like their [`Deserialize`](de::Deserialize) implementations do.
+///
+/// Serialization is unchanged.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct BorrowCow;
+
+#[derive(Debug, Copy, Clone, Default)]
+pub struct BorrowCowSeed<T>(PhantomData<T>);
+
+impl<'de: 'a, 'a> DeSeeder<'de, Cow<'a, str>> for BorrowCow {
+	type Seed = BorrowCowSeed<Cow<'a, str>>;
+	fn seed(self) -> Self::Seed {
+		BorrowCowSeed(PhantomData)
+	}
+}
+
+impl<'de: 'a, 'a> DeSeeder<'de, Cow<'a, [u8]>> for BorrowCow {
+	type Seed = BorrowCowSeed<Cow<'a, [u8]>>;
+	fn seed(self) -> Self::Seed {
+		BorrowCowSeed(PhantomData)
+	}
+}
+
+impl<'a, T: ?Sized + ToOwned + Serialize> SerSeeder<Cow<'a, T>> for BorrowCow {
+	fn seeded<'s>(&'s self, value: &'s Cow<'a, T>) -> Seeded<'s> {
+		Box::new(value)
+	}
+}
+
+impl<'de: 'a, 'a> de::DeserializeSeed<'de> for BorrowCowSeed<Cow<'a, str>> {
+	type Value = Cow<'a, str>;
+
+	fn deserialize<D: de::Deserializer<'de>>(
+		self,
+		deserializer: D,
+	) -> Result<Self::Value, D::Error> {
+		deserializer.deserialize_str(self)
+	}
+}
+
+impl<'de: 'a, 'a> de::Visitor<'de> for BorrowCowSeed<Cow<'a, str>> {
+	type Value = Cow<'a, str>;
+
+	fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("a string")
+	}
+
+	fn visit_borrowed_str<E: de::Error>(self, v: &'de str) -> Result<Self::Value, E> {
+		Ok(Cow::Borrowed(v))
+	}
+
+	fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+		Ok(Cow::Owned(v.to_owned()))
+	}
+
+	fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
+		Ok(Cow::Owned(v))
+	}
+
+	fn visit_borrowed_bytes<E: de::Error>(self, v: &'de [u8]) -> Result<Self::Value, E> {
+		std::str::from_utf8(v)
+			.map(Cow::Borrowed)
+			.map_err(|_| de::Error::invalid_value(de::Unexpected::Bytes(v), &self))
+	}
+
+	fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+		std::str::from_utf8(v)
+			.map(|v| Cow::Owned(v.to_owned()))
+			.map_err(|_| de::Error::invalid_value(de::Unexpected::Bytes(v), &self))
+	}
+}
+
+impl<'de: 'a, 'a> de::DeserializeSeed<'de> for BorrowCowSeed<Cow<'a, [u8]>> {
+	type Value = Cow<'a, [u8]>;
+
+	fn deserialize<D: de::Deserializer<'de>>(
+		self,
+		deserializer: D,
+	) -> Result<Self::Value, D::Error> {
+		deserializer.deserialize_bytes(self)
+	}
+}
+
+impl<'de: 'a, 'a> de::Visitor<'de> for BorrowCowSeed<Cow<'a, [u8]>> {
+	type Value = Cow<'a, [u8]>;
+
+	fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("a byte array")
+	}
+
+	fn visit_borrowed_bytes<E: de::Error>(self, v: &'de [u8]) -> Result<Self::Value, E> {
+		Ok(Cow::Borrowed(v))
+	}
+
+	fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+		Ok(Cow::Owned(v.to_owned()))
+	}
+
+	fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+		Ok(Cow::Owned(v))
+	}
+
+	fn visit_borrowed_str<E: de::Error>(self, v: &'de str) -> Result<Self::Value, E> {
+		Ok(Cow::Borrowed(v.as_bytes()))
+	}
+
+	fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+		Ok(Cow::Owned(v.as_bytes().to_owned()))
+	}
+
+	fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
+		Ok(Cow::Owned(v.into_bytes()))
+	}
+
+	fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+		let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+		while let Some(byte) = seq.next_element()? {
+			bytes.push(byte);
+		}
+		Ok(Cow::Owned(bytes))
+	}
+}
+
+/// Applies the wrapped seeder to each element of an array, which is (de)serialized as tuple like with plain serde.
+///
+/// The seeder is cloned for each element when deserializing.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct ArraySeeder<S>(pub S);
+
+#[derive(Debug, Copy, Clone)]
+pub struct ArraySeed<S, T, const N: usize>(S, PhantomData<fn() -> [T; N]>);
+
+impl<'de, S: DeSeeder<'de, T> + Clone, T, const N: usize> DeSeeder<'de, [T; N]> for ArraySeeder<S> {
+	type Seed = ArraySeed<S, T, N>;
+	fn seed(self) -> Self::Seed {
+		ArraySeed(self.0, PhantomData)
+	}
+}
+
+impl<S: SerSeeder<T>, T, const N: usize> SerSeeder<[T; N]> for ArraySeeder<S> {
+	fn seeded<'s>(&'s self, value: &'s [T; N]) -> Seeded<'s> {
+		Box::new(ArraySeeded(&self.0, value))
+	}
+}
+
+impl<'de, S: DeSeeder<'de, T> + Clone, T, const N: usize> de::DeserializeSeed<'de>
+	for ArraySeed<S, T, N>
+{
+	type Value = [T; N];
+
+	fn deserialize<D: de::Deserializer<'de>>(
+		self,
+		deserializer: D,
+	) -> Result<Self::Value, D::Error> {
+		deserializer.deserialize_tuple(N, self)
+	}
+}
+
+impl<'de, S: DeSeeder<'de, T> + Clone, T, const N: usize> de::Visitor<'de> for ArraySeed<S, T, N> {
+	type Value = [T; N];
+
+	fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "an array of length {}", N)
+	}
+
+	fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+		let mut elements = Vec::with_capacity(N);
+		for i in 0..N {
+			elements.push(
+				seq.next_element_seed(self.0.clone().seed())?
+					.ok_or_else(|| de::Error::invalid_length(i, &self))?,
+			);
+		}
+		// Like serde's own `SeqDeserializer`, report the total length.
+		let mut surplus = 0;
+		while let Some(de::IgnoredAny) = seq.next_element()? {
+			surplus += 1;
+		}
+		if surplus > 0 {
+			return Err(de::Error::invalid_length(N + surplus, &self));
+		}
+		match elements.try_into() {
+			Ok(array) => Ok(array),
+			Err(_) => unreachable!(),
+		}
+	}
+}
+
+struct ArraySeeded<'s, S, T, const N: usize>(&'s S, &'s [T; N]);
+
+impl<'s, S: SerSeeder<T>, T, const N: usize> Serialize for ArraySeeded<'s, S, T, N> {
+	fn serialize<Ser: serde::Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+		let mut tuple = serializer.serialize_tuple(N)?;
+		for element in self.1 {
+			tuple.serialize_element(&self.0.seeded(element))?;
+		}
+		tuple.end()
+	}
+}
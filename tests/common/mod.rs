@@ -0,0 +1,78 @@
+//! Helpers shared by the integration tests.
+#![allow(dead_code)]
+
+use bincode::Options as _;
+use serde::{
+	de::{self, DeserializeSeed},
+	Serialize,
+};
+use serde_seeded::{DeSeeder, Seeded, SerSeeder};
+use std::marker::PhantomData;
+
+/// Writes values divided by the scale and multiplies them when reading them back,
+/// so that a round trip only succeeds if the seed arguments reach the fields.
+#[derive(Clone, Copy)]
+pub struct Scaled<'a>(pub &'a u32);
+
+impl<'de> DeSeeder<'de, u32> for Scaled<'_> {
+	type Seed = Self;
+	fn seed(self) -> Self::Seed {
+		self
+	}
+}
+
+impl<'de> DeserializeSeed<'de> for Scaled<'_> {
+	type Value = u32;
+	fn deserialize<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<u32, D::Error> {
+		Ok(<u32 as de::Deserialize>::deserialize(deserializer)? * self.0)
+	}
+}
+
+impl SerSeeder<u32> for Scaled<'_> {
+	fn seeded<'s>(&'s self, value: &'s u32) -> Seeded<'s> {
+		Box::new(value / self.0)
+	}
+}
+
+/// (De)serializes fields through their own serde implementations.
+pub struct Plain<T>(PhantomData<T>);
+
+pub fn plain<T>() -> Plain<T> {
+	Plain(PhantomData)
+}
+
+impl<'de, T: de::Deserialize<'de>> DeSeeder<'de, T> for Plain<T> {
+	type Seed = PhantomData<T>;
+	fn seed(self) -> Self::Seed {
+		PhantomData
+	}
+}
+
+impl<T: Serialize> SerSeeder<T> for Plain<T> {
+	fn seeded<'s>(&'s self, value: &'s T) -> Seeded<'s> {
+		Box::new(value)
+	}
+}
+
+pub fn to_json(value: impl Serialize) -> String {
+	serde_json::to_string(&value).unwrap()
+}
+
+pub fn from_json<'de, S: DeserializeSeed<'de>>(seed: S, json: &'de str) -> S::Value {
+	seed.deserialize(&mut serde_json::Deserializer::from_str(json))
+		.unwrap()
+}
+
+pub fn to_bincode(value: impl Serialize) -> Vec<u8> {
+	bincode::options()
+		.with_fixint_encoding()
+		.serialize(&value)
+		.unwrap()
+}
+
+pub fn from_bincode<'de, S: DeserializeSeed<'de>>(seed: S, bytes: &'de [u8]) -> S::Value {
+	bincode::options()
+		.with_fixint_encoding()
+		.deserialize_seed(seed, bytes)
+		.unwrap()
+}
@@ -0,0 +1,82 @@
+//! Round trips of types with const generic parameters and of arrays, whose elements are seeded one by one.
+
+use serde::de::DeserializeSeed;
+use serde_seeded::{seed, seeded, ArraySeeder};
+
+mod common;
+use common::*;
+
+/// The view lends its arguments, so `scale` is a reference there.
+#[derive(Debug, PartialEq, seed, seeded)]
+#[seed_args(scale: u32)]
+struct Row<const N: usize> {
+	#[seeded_de(ArraySeeder(Scaled(&scale)))]
+	#[seeded_ser(ArraySeeder(Scaled(scale)))]
+	cells: [u32; N],
+}
+
+#[test]
+fn arrays() {
+	let row = Row {
+		cells: [10, 20, 30],
+	};
+
+	let json = to_json(row.seeded(10));
+	assert_eq!(json, r#"{"cells":[1,2,3]}"#);
+	assert_eq!(from_json(Row::seed(10), &json), row);
+
+	let bytes = to_bincode(row.seeded(10));
+	assert_eq!(bytes, to_bincode([1_u32, 2, 3]));
+	assert_eq!(from_bincode(Row::seed(10), &bytes), row);
+
+	let empty = Row { cells: [] };
+	assert_eq!(to_json(empty.seeded(10)), r#"{"cells":[]}"#);
+	assert_eq!(from_json(Row::<0>::seed(10), r#"{"cells":[]}"#), empty);
+}
+
+#[test]
+fn lengths() {
+	let error = |json: &str| {
+		Row::<3>::seed(10)
+			.deserialize(&mut serde_json::Deserializer::from_str(json))
+			.unwrap_err()
+			.to_string()
+	};
+	assert_eq!(
+		error(r#"{"cells":[1,2]}"#),
+		"invalid length 2, expected an array of length 3 at line 1 column 14",
+	);
+	assert_eq!(
+		error(r#"{"cells":[1,2,3,4,5]}"#),
+		"invalid length 5, expected an array of length 3 at line 1 column 20",
+	);
+}
+
+/// Scales its first and last field by the first and last of any number of scales.
+#[derive(Debug, PartialEq, seed, seeded)]
+#[seed_generics(const N: usize)]
+#[seed_args(scales: [u32; N])]
+struct Ends {
+	#[seeded(Scaled(&scales[0]))]
+	first: u32,
+	#[seeded(Scaled(&scales[N - 1]))]
+	last: u32,
+}
+
+#[test]
+fn seed_generics() {
+	let ends = Ends {
+		first: 20,
+		last: 300,
+	};
+	let json = to_json(ends.seeded([10, 20, 30]));
+	assert_eq!(json, r#"{"first":2,"last":10}"#);
+	assert_eq!(from_json(Ends::seed([10, 20, 30]), &json), ends);
+	assert_eq!(
+		from_json(Ends::seed([10]), "[2,30]"),
+		Ends {
+			first: 20,
+			last: 300,
+		},
+	);
+}
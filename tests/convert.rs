@@ -0,0 +1,166 @@
+//! Round trips through intermediate types with `seed_from`, `seed_try_from` and `seeded_into`.
+//!
+//! Without `seeded`, `forward` or `seeder = expr`, the intermediate type is (de)serialized through
+//! its own serde implementation, and only the conversion functions see the seed arguments.
+
+use serde::{de::DeserializeSeed, Deserialize, Serialize};
+use serde_seeded::{seed, seeded};
+
+mod common;
+use common::*;
+
+#[derive(Deserialize, Serialize)]
+struct RawPercent {
+	value: u32,
+}
+
+fn percent_from_raw(raw: RawPercent, scale: &u32) -> Result<Percent, String> {
+	if raw.value > *scale {
+		Err(format!("{} is out of range", raw.value))
+	} else {
+		Ok(Percent {
+			fraction: raw.value as f32 / *scale as f32,
+		})
+	}
+}
+
+fn percent_into_raw(percent: &Percent, scale: &&u32) -> RawPercent {
+	RawPercent {
+		value: (percent.fraction * **scale as f32) as u32,
+	}
+}
+
+#[derive(Debug, PartialEq, seed, seeded)]
+#[seed_args(scale: &u32)]
+#[seed_try_from(RawPercent, with = percent_from_raw)]
+#[seeded_into(RawPercent, with = percent_into_raw)]
+struct Percent {
+	fraction: f32,
+}
+
+#[test]
+fn fallible() {
+	let percent = Percent { fraction: 0.25 };
+
+	let json = to_json(percent.seeded(&200));
+	assert_eq!(json, r#"{"value":50}"#);
+	assert_eq!(from_json(Percent::seed(&200), &json), percent);
+
+	let bytes = to_bincode(percent.seeded(&200));
+	assert_eq!(bytes, to_bincode(50_u32));
+	assert_eq!(from_bincode(Percent::seed(&200), &bytes), percent);
+
+	let error = Percent::seed(&200)
+		.deserialize(&mut serde_json::Deserializer::from_str(r#"{"value":500}"#))
+		.unwrap_err();
+	// The conversion runs once the input is read, so the error has no position.
+	assert_eq!(error.to_string(), "500 is out of range");
+}
+
+/// Only has the derived seed and view, which `seeded` uses.
+#[derive(seed, seeded)]
+struct RawName(String);
+
+fn name_from_raw(raw: RawName, prefix: &str) -> Name {
+	Name(format!("{}{}", prefix, raw.0))
+}
+
+fn name_into_raw(name: &Name, prefix: &&str) -> RawName {
+	RawName(name.0.trim_start_matches(prefix).to_owned())
+}
+
+#[derive(Debug, PartialEq, seed, seeded)]
+#[seed_args(prefix: &str)]
+#[seed_from(RawName, with = name_from_raw, seeded)]
+#[seeded_into(RawName, with = name_into_raw, seeded)]
+struct Name(String);
+
+/// Read through an explicit seeder, which sees the seed arguments too.
+#[derive(Debug, PartialEq, seed, seeded)]
+#[seed_args(scale: &u32)]
+#[seed_from(u32, with = count_from_raw, seeder = Scaled(scale))]
+#[seeded_into(u32, with = count_into_raw, seeder = Scaled(scale))]
+struct Count(u32);
+
+fn count_from_raw(raw: u32, _scale: &u32) -> Count {
+	Count(raw)
+}
+
+fn count_into_raw(count: &Count, _scale: &&u32) -> u32 {
+	count.0
+}
+
+#[test]
+fn infallible() {
+	let name = Name("x-name".to_owned());
+	let json = to_json(name.seeded("x-"));
+	assert_eq!(json, r#""name""#);
+	assert_eq!(from_json(Name::seed("x-"), &json), name);
+
+	let bytes = to_bincode(name.seeded("x-"));
+	assert_eq!(from_bincode(Name::seed("x-"), &bytes), name);
+
+	let count = Count(30);
+	let json = to_json(count.seeded(&10));
+	assert_eq!(json, "3");
+	assert_eq!(from_json(Count::seed(&10), &json), count);
+	let bytes = to_bincode(count.seeded(&10));
+	assert_eq!(from_bincode(Count::seed(&10), &bytes), count);
+}
+
+/// Not `Clone`, so `seeded_into` functions only borrow it from the view.
+struct Unit(String);
+
+#[derive(seeded)]
+#[seed_args(unit: Unit)]
+#[seeded_into(String, with = length_into_raw)]
+struct Length(u32);
+
+fn length_into_raw(length: &Length, unit: &Unit) -> String {
+	format!("{}{}", length.0, unit.0)
+}
+
+#[test]
+fn borrowed_argument() {
+	let json = to_json(Length(3).seeded(Unit("m".to_owned())));
+	assert_eq!(json, r#""3m""#);
+}
+
+/// Its field is neither read nor written itself, so `T` only needs what the conversion functions ask for.
+#[derive(Debug, PartialEq, seed, seeded)]
+#[seed_where_de(T: From<u32>)]
+#[seed_where_ser(T: Clone + Into<u32>)]
+#[seed_from(u32, with = opaque_from_raw)]
+#[seeded_into(u32, with = opaque_into_raw)]
+struct Opaque<T>(T);
+
+fn opaque_from_raw<T: From<u32>>(raw: u32) -> Opaque<T> {
+	Opaque(raw.into())
+}
+
+fn opaque_into_raw<T: Clone + Into<u32>>(opaque: &Opaque<T>) -> u32 {
+	opaque.0.clone().into()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Token(u32);
+
+impl From<u32> for Token {
+	fn from(raw: u32) -> Self {
+		Token(raw)
+	}
+}
+
+impl From<Token> for u32 {
+	fn from(token: Token) -> Self {
+		token.0
+	}
+}
+
+#[test]
+fn generic_without_bounds() {
+	let opaque = Opaque(Token(3));
+	let json = to_json(opaque.seeded());
+	assert_eq!(json, "3");
+	assert_eq!(from_json(Opaque::seed(), &json), opaque);
+}
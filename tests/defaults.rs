@@ -0,0 +1,70 @@
+//! Values for fields missing from the input, in both sequences and maps.
+
+use serde_seeded::seed;
+
+mod common;
+use common::*;
+
+#[derive(Debug, PartialEq, seed)]
+#[seed_args(fallback: u32)]
+struct Settings {
+	id: u32,
+	#[seeded_de(default)]
+	tags: Vec<String>,
+	#[seeded_de(default = fallback * 2)]
+	limit: u32,
+	note: Option<String>,
+}
+
+#[test]
+fn seq() {
+	assert_eq!(
+		from_json(Settings::seed(5), "[1]"),
+		Settings {
+			id: 1,
+			tags: vec![],
+			limit: 10,
+			note: None,
+		},
+	);
+	assert_eq!(
+		from_json(Settings::seed(5), r#"[1,["a"]]"#),
+		Settings {
+			id: 1,
+			tags: vec!["a".to_owned()],
+			limit: 10,
+			note: None,
+		},
+	);
+	assert_eq!(
+		from_json(Settings::seed(5), r#"[1,[],3,"note"]"#),
+		Settings {
+			id: 1,
+			tags: vec![],
+			limit: 3,
+			note: Some("note".to_owned()),
+		},
+	);
+}
+
+#[test]
+fn map() {
+	assert_eq!(
+		from_json(Settings::seed(5), r#"{"id":1}"#),
+		Settings {
+			id: 1,
+			tags: vec![],
+			limit: 10,
+			note: None,
+		},
+	);
+	assert_eq!(
+		from_json(Settings::seed(5), r#"{"note":"note","id":1,"limit":3}"#),
+		Settings {
+			id: 1,
+			tags: vec![],
+			limit: 3,
+			note: Some("note".to_owned()),
+		},
+	);
+}
@@ -0,0 +1,198 @@
+//! Round trips of structs whose field seeders refer to earlier fields,
+//! including map input where the dependent field comes first and has to be buffered.
+
+use serde::de::{self, DeserializeSeed};
+use serde_seeded::{seed, seeded, DeSeeder, Seeded, SerSeeder};
+
+mod common;
+use common::*;
+
+#[derive(Debug, PartialEq)]
+enum Payload {
+	Number(u32),
+	Text(String),
+}
+
+/// Reads a number (plus the offset) or a text, depending on `kind`.
+struct Typed<'a> {
+	kind: &'a u8,
+	offset: &'a u32,
+}
+
+impl<'de> DeSeeder<'de, Payload> for Typed<'_> {
+	type Seed = Self;
+	fn seed(self) -> Self::Seed {
+		self
+	}
+}
+
+impl<'de> DeserializeSeed<'de> for Typed<'_> {
+	type Value = Payload;
+	fn deserialize<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<Payload, D::Error> {
+		Ok(match self.kind {
+			0 => {
+				Payload::Number(<u32 as de::Deserialize>::deserialize(deserializer)? + self.offset)
+			}
+			_ => Payload::Text(de::Deserialize::deserialize(deserializer)?),
+		})
+	}
+}
+
+impl SerSeeder<Payload> for Typed<'_> {
+	fn seeded<'s>(&'s self, value: &'s Payload) -> Seeded<'s> {
+		match value {
+			Payload::Number(number) => Box::new(number - self.offset),
+			Payload::Text(text) => Box::new(text),
+		}
+	}
+}
+
+#[derive(Debug, PartialEq, seed, seeded)]
+#[seed_args(offset: &u32)]
+struct Item {
+	kind: u8,
+	#[seeded_de(Typed { kind: &kind, offset })]
+	#[seeded_ser(Typed { kind, offset })]
+	payload: Payload,
+	count: u32,
+}
+
+fn samples() -> Vec<Item> {
+	vec![
+		Item {
+			kind: 0,
+			payload: Payload::Number(105),
+			count: 1,
+		},
+		Item {
+			kind: 1,
+			payload: Payload::Text("text".to_owned()),
+			count: 2,
+		},
+	]
+}
+
+#[test]
+fn round_trip() {
+	let expected = [
+		r#"{"kind":0,"payload":5,"count":1}"#,
+		r#"{"kind":1,"payload":"text","count":2}"#,
+	];
+	for (item, expected) in samples().into_iter().zip(expected.iter()) {
+		let json = to_json(item.seeded(&100));
+		assert_eq!(json, *expected);
+		assert_eq!(from_json(Item::seed(&100), &json), item);
+
+		let bytes = to_bincode(item.seeded(&100));
+		assert_eq!(from_bincode(Item::seed(&100), &bytes), item);
+	}
+}
+
+#[test]
+fn buffered() {
+	// The payload arrives before the kind it depends on.
+	let mut items = samples().into_iter();
+	assert_eq!(
+		from_json(Item::seed(&100), r#"{"payload":5,"count":1,"kind":0}"#),
+		items.next().unwrap(),
+	);
+	assert_eq!(
+		from_json(Item::seed(&100), r#"{"count":2,"payload":"text","kind":1}"#),
+		items.next().unwrap(),
+	);
+}
+
+#[test]
+fn errors() {
+	let error = |json: &str| {
+		Item::seed(&100)
+			.deserialize(&mut serde_json::Deserializer::from_str(json))
+			.unwrap_err()
+			.to_string()
+	};
+	assert_eq!(
+		error(r#"{"payload":5,"count":1}"#),
+		"missing field `kind` at line 1 column 23",
+	);
+	assert_eq!(
+		error(r#"{"payload":5,"kind":0,"payload":6,"count":1}"#),
+		"duplicate field `payload` at line 1 column 31",
+	);
+	// A buffered payload is only read once the kind is known.
+	assert_eq!(
+		error(r#"{"payload":"text","count":1,"kind":0}"#),
+		r#"invalid type: string "text", expected u32 at line 1 column 37"#,
+	);
+}
+
+#[derive(Debug, PartialEq, seed, seeded)]
+#[seed_args(offset: &u32)]
+enum Wrapped {
+	Item {
+		kind: u8,
+		#[seeded_de(Typed { kind: &kind, offset })]
+		#[seeded_ser(Typed { kind, offset })]
+		payload: Payload,
+	},
+}
+
+#[test]
+fn variant() {
+	let wrapped = Wrapped::Item {
+		kind: 0,
+		payload: Payload::Number(105),
+	};
+	let json = to_json(wrapped.seeded(&100));
+	assert_eq!(json, r#"{"Item":{"kind":0,"payload":5}}"#);
+	assert_eq!(from_json(Wrapped::seed(&100), &json), wrapped);
+	assert_eq!(
+		from_json(Wrapped::seed(&100), r#"{"Item":{"payload":5,"kind":0}}"#),
+		wrapped,
+	);
+
+	let bytes = to_bincode(wrapped.seeded(&100));
+	assert_eq!(from_bincode(Wrapped::seed(&100), &bytes), wrapped);
+}
+
+#[derive(Debug, PartialEq, seed)]
+#[seed_args(scale: u32)]
+struct Shadowed {
+	scale: u32,
+	#[seeded(Scaled(&scale))]
+	value: u32,
+}
+
+#[test]
+fn arguments_first() {
+	// A seed argument is preferred over an earlier field of the same name.
+	let shadowed = Shadowed {
+		scale: 2,
+		value: 30,
+	};
+	assert_eq!(
+		from_json(Shadowed::seed(10), r#"{"scale":2,"value":3}"#),
+		shadowed,
+	);
+	assert_eq!(
+		from_json(Shadowed::seed(10), r#"{"value":3,"scale":2}"#),
+		shadowed,
+	);
+	assert_eq!(from_json(Shadowed::seed(10), "[2,3]"), shadowed);
+}
+
+#[derive(Debug, PartialEq, seed, seeded)]
+#[seed_args(scale: &u32)]
+struct Measured {
+	scale: u32,
+	#[seeded(Scaled(scale))]
+	size: u32,
+}
+
+#[test]
+fn arguments_first_when_serializing() {
+	// The field `scale` doesn't hide the seed argument from the seeder of `size` either.
+	let measured = Measured { scale: 1, size: 30 };
+	let json = to_json(measured.seeded(&10));
+	assert_eq!(json, r#"{"scale":1,"size":3}"#);
+	assert_eq!(from_json(Measured::seed(&10), &json), measured);
+}
@@ -0,0 +1,86 @@
+//! What the derived seeds say they expected when the input doesn't fit.
+// The values are only ever rejected, so their fields are never read.
+#![allow(dead_code)]
+
+use serde::de::DeserializeSeed;
+use serde_seeded::seed;
+
+#[derive(Debug, seed)]
+struct Pair {
+	a: u32,
+	b: u32,
+}
+
+#[derive(Debug, seed)]
+struct Tuple(u32, u32);
+
+#[derive(Debug, seed)]
+struct Marker;
+
+#[derive(Debug, seed)]
+enum Shape {
+	Unit,
+	Tuple(u32, u32),
+	Struct { a: u32 },
+}
+
+fn error<'de, S: DeserializeSeed<'de>>(seed: S, json: &'de str) -> String
+where
+	S::Value: std::fmt::Debug,
+{
+	seed.deserialize(&mut serde_json::Deserializer::from_str(json))
+		.unwrap_err()
+		.to_string()
+}
+
+#[test]
+fn structs() {
+	assert_eq!(
+		error(Pair::seed(), r#""x""#),
+		r#"invalid type: string "x", expected struct Pair with 2 elements at line 1 column 3"#,
+	);
+	assert_eq!(
+		error(Pair::seed(), "[1]"),
+		"invalid length 1, expected struct Pair with 2 elements, but field `b` is missing at line 1 column 3",
+	);
+	assert_eq!(
+		error(Pair::seed(), "[1,2,3]"),
+		"invalid length 3, expected struct Pair with 2 elements at line 1 column 7",
+	);
+	assert_eq!(
+		error(Tuple::seed(), r#""x""#),
+		r#"invalid type: string "x", expected tuple struct Tuple with 2 elements at line 1 column 3"#,
+	);
+	assert_eq!(
+		error(Tuple::seed(), "[1]"),
+		"invalid length 1, expected tuple struct Tuple with 2 elements, but field `1` is missing at line 1 column 3",
+	);
+	assert_eq!(
+		error(Marker::seed(), "1"),
+		"invalid type: integer `1`, expected unit struct Marker at line 1 column 1",
+	);
+}
+
+#[test]
+fn variants() {
+	assert_eq!(
+		error(Shape::seed(), r#"{"Unit":1}"#),
+		"invalid type: integer `1`, expected unit at line 1 column 9",
+	);
+	assert_eq!(
+		error(Shape::seed(), r#"{"Tuple":[1]}"#),
+		"invalid length 1, expected tuple variant Shape::Tuple with 2 elements, but field `1` is missing at line 1 column 12",
+	);
+	assert_eq!(
+		error(Shape::seed(), r#"{"Struct":1}"#),
+		"invalid type: integer `1`, expected struct variant Shape::Struct with 1 element at line 1 column 11",
+	);
+	assert_eq!(
+		error(Shape::seed(), r#"{"Struct":[]}"#),
+		"invalid length 0, expected struct variant Shape::Struct with 1 element, but field `a` is missing at line 1 column 12",
+	);
+	assert_eq!(
+		error(Shape::seed(), r#""Other""#),
+		"unknown variant `Other`, expected one of `Unit`, `Tuple`, `Struct` at line 1 column 7",
+	);
+}
@@ -0,0 +1,77 @@
+//! The finishing step, which gets the deserialized value and the seed arguments and can replace the value.
+
+use serde::de::DeserializeSeed;
+use serde_seeded::seed;
+use std::rc::Rc;
+
+mod common;
+use common::*;
+
+#[derive(Default)]
+struct World {
+	next_id: u32,
+	names: Vec<Rc<str>>,
+}
+
+/// Registers the entity in the world with the next ID after `offset`, and interns its name.
+fn register(entity: Entity, world: &mut World, offset: u32) -> Result<Entity, String> {
+	if entity.name.is_empty() {
+		return Err("entities need a name".to_owned());
+	}
+	let id = offset + world.next_id;
+	world.next_id += 1;
+	let interned = match world.names.iter().find(|name| ***name == *entity.name) {
+		Some(name) => name.clone(),
+		None => {
+			let name: Rc<str> = entity.name.as_str().into();
+			world.names.push(name.clone());
+			name
+		}
+	};
+	Ok(Entity {
+		id,
+		interned: Some(interned),
+		..entity
+	})
+}
+
+#[derive(Debug, PartialEq, seed)]
+#[seed_args(world: &mut World, offset: u32)]
+#[seed_finish(register)]
+struct Entity {
+	#[seeded(skip)]
+	id: u32,
+	name: String,
+	#[seeded(skip)]
+	interned: Option<Rc<str>>,
+}
+
+#[test]
+fn registered() {
+	let mut world = World::default();
+	let first = from_json(Entity::seed(&mut world, 100), r#"{"name":"tree"}"#);
+	let second = from_bincode(Entity::seed(&mut world, 100), &to_bincode(("tree",)));
+	let third = from_json(Entity::seed(&mut world, 200), r#"["rock"]"#);
+
+	assert_eq!((first.id, second.id, third.id), (100, 101, 202));
+	assert_eq!(world.next_id, 3);
+	assert_eq!(world.names.len(), 2);
+	assert!(Rc::ptr_eq(
+		first.interned.as_ref().unwrap(),
+		second.interned.as_ref().unwrap(),
+	));
+	assert_eq!(third.interned.as_deref(), Some("rock"));
+}
+
+#[test]
+fn rejected() {
+	let mut world = World::default();
+	let error = Entity::seed(&mut world, 0)
+		.deserialize(&mut serde_json::Deserializer::from_str(r#"{"name":""}"#))
+		.unwrap_err();
+	assert_eq!(
+		error.to_string(),
+		"entities need a name at line 1 column 11"
+	);
+	assert_eq!(world.next_id, 0);
+}
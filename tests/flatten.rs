@@ -0,0 +1,148 @@
+//! Round trips of structs with flattened fields, and what happens to the keys no field claims.
+//!
+//! Like with serde_derive, flattened structs are written as maps of unknown length,
+//! which sequential formats like bincode can't represent, so only JSON is used here.
+
+use serde::de::DeserializeSeed;
+use serde_seeded::{seed, seeded, FnDeSeeder, FnSerSeeder};
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
+
+mod common;
+use common::*;
+
+#[derive(Debug, PartialEq, seed, seeded)]
+#[seed_args(scale: u32)]
+struct Window {
+	#[seeded_de(Scaled(&scale))]
+	#[seeded_ser(Scaled(scale))]
+	width: u32,
+	height: u32,
+}
+
+#[derive(Debug, PartialEq, seed, seeded)]
+#[seed_args(scale: u32)]
+struct Config {
+	name: String,
+	#[seeded_de(flatten, FnDeSeeder(|| Window::seed(scale)))]
+	#[seeded_ser(flatten, FnSerSeeder::new(|value: &Window| Box::new(value.seeded(*scale))))]
+	window: Window,
+	/// Whatever is left over.
+	#[seeded(flatten)]
+	rest: BTreeMap<String, u32>,
+}
+
+#[test]
+fn leftovers() {
+	let config = Config {
+		name: "main".to_owned(),
+		window: Window {
+			width: 200,
+			height: 3,
+		},
+		rest: vec![("x".to_owned(), 1), ("y".to_owned(), 2)]
+			.into_iter()
+			.collect(),
+	};
+
+	let json = to_json(config.seeded(10));
+	assert_eq!(json, r#"{"name":"main","width":20,"height":3,"x":1,"y":2}"#);
+	assert_eq!(from_json(Config::seed(10), &json), config);
+
+	// The keys may come in any order.
+	assert_eq!(
+		from_json(
+			Config::seed(10),
+			r#"{"y":2,"height":3,"x":1,"name":"main","width":20}"#
+		),
+		config,
+	);
+}
+
+#[derive(Debug, PartialEq, seed, seeded)]
+struct Inner {
+	b: u32,
+}
+
+#[derive(Debug, PartialEq, seed, seeded)]
+#[seed_unknown_fields(deny)]
+struct Strict {
+	a: u32,
+	#[seeded]
+	#[seeded(flatten)]
+	inner: Inner,
+}
+
+#[test]
+fn deny() {
+	let strict = Strict {
+		a: 1,
+		inner: Inner { b: 2 },
+	};
+	let json = to_json(strict.seeded());
+	assert_eq!(json, r#"{"a":1,"b":2}"#);
+	assert_eq!(from_json(Strict::seed(), &json), strict);
+
+	// Keys are only unknown if no flattened field claims them either.
+	let error = Strict::seed()
+		.deserialize(&mut serde_json::Deserializer::from_str(
+			r#"{"a":1,"b":2,"c":3}"#,
+		))
+		.unwrap_err();
+	assert_eq!(
+		error.to_string(),
+		"unknown field `c`, expected `a` at line 1 column 19",
+	);
+}
+
+#[derive(Debug, PartialEq, seed)]
+#[seed_args(unknown: Rc<RefCell<Vec<String>>>)]
+#[seed_unknown_fields(with = |key: &str, _| unknown.borrow_mut().push(key.to_owned()))]
+struct Collect {
+	#[seeded]
+	#[seeded(flatten)]
+	inner: Inner,
+}
+
+#[test]
+fn collect() {
+	let unknown = Rc::new(RefCell::new(vec![]));
+	assert_eq!(
+		from_json(
+			Collect::seed(Rc::clone(&unknown)),
+			r#"{"z":[1],"b":2,"c":{"d":3}}"#
+		),
+		Collect {
+			inner: Inner { b: 2 }
+		},
+	);
+	assert_eq!(*unknown.borrow(), ["z", "c"]);
+}
+
+#[derive(Debug, PartialEq, seed, seeded)]
+struct Aliased {
+	#[seeded_de(alias = "aa")]
+	a: u32,
+}
+
+#[derive(Debug, PartialEq, seed, seeded)]
+struct Outer {
+	x: u32,
+	#[seeded]
+	#[seeded(flatten)]
+	inner: Aliased,
+	#[seeded(flatten)]
+	rest: BTreeMap<String, u32>,
+}
+
+#[test]
+fn alias() {
+	// Flattened fields are found under their aliases too, and the entries they ignore are left for later ones.
+	assert_eq!(
+		from_json(Outer::seed(), r#"{"x":1,"aa":2,"z":3}"#),
+		Outer {
+			x: 1,
+			inner: Aliased { a: 2 },
+			rest: vec![("z".to_owned(), 3)].into_iter().collect(),
+		},
+	);
+}
@@ -0,0 +1,75 @@
+//! Round trips of types with lifetime parameters, which borrow from the input where it allows that.
+
+use serde_seeded::{seed, seeded, BorrowCow};
+use std::borrow::Cow;
+
+mod common;
+use common::*;
+
+/// The view lends its arguments, so `scale` is a reference there.
+#[derive(Debug, PartialEq, seed, seeded)]
+#[seed_args(scale: u32)]
+struct Entry<'a> {
+	#[seeded(plain())]
+	name: &'a str,
+	#[seeded(BorrowCow)]
+	text: Cow<'a, str>,
+	#[seeded_de(Scaled(&scale))]
+	#[seeded_ser(Scaled(scale))]
+	size: u32,
+}
+
+#[derive(Debug, PartialEq, seed, seeded)]
+enum Either<'a, 'b> {
+	Left(#[seeded(BorrowCow)] Cow<'a, str>),
+	Right(#[seeded(BorrowCow)] Cow<'b, [u8]>),
+}
+
+#[test]
+fn borrowed() {
+	let entry = Entry {
+		name: "name",
+		text: Cow::Borrowed("text"),
+		size: 30,
+	};
+
+	let json = to_json(entry.seeded(10));
+	assert_eq!(json, r#"{"name":"name","text":"text","size":3}"#);
+	let read = from_json(Entry::seed(10), &json);
+	assert_eq!(read, entry);
+	assert!(matches!(read.text, Cow::Borrowed(_)));
+
+	let bytes = to_bincode(entry.seeded(10));
+	let read = from_bincode(Entry::seed(10), &bytes);
+	assert_eq!(read, entry);
+	assert!(matches!(read.text, Cow::Borrowed(_)));
+}
+
+#[test]
+fn owned() {
+	// Escaped strings can't be borrowed from JSON, so only the `Cow` still works.
+	let read = from_json(
+		Entry::seed(10),
+		r#"{"name":"name","text":"te\u0078t","size":3}"#,
+	);
+	assert_eq!(read.text, "text");
+	assert!(matches!(read.text, Cow::Owned(_)));
+}
+
+#[test]
+fn variants() {
+	let left = Either::Left(Cow::Borrowed("left"));
+	let json = to_json(left.seeded());
+	assert_eq!(json, r#"{"Left":"left"}"#);
+	assert!(matches!(
+		from_json(Either::seed(), &json),
+		Either::Left(Cow::Borrowed("left")),
+	));
+
+	let right = Either::Right(Cow::Borrowed(b"right"));
+	let bytes = to_bincode(right.seeded());
+	assert!(matches!(
+		from_bincode(Either::seed(), &bytes),
+		Either::Right(Cow::Borrowed(b"right")),
+	));
+}
@@ -0,0 +1,112 @@
+//! Map input, as self-describing formats hand structs over.
+
+use serde::de::{self, Deserialize, DeserializeSeed};
+use serde_seeded::{seed, DeSeeder};
+
+mod common;
+use common::*;
+
+#[derive(Debug, PartialEq, seed)]
+#[seed_args(scale: u32)]
+struct Config {
+	#[seeded(plain())]
+	name: String,
+	#[seeded(Scaled(&scale))]
+	size: u32,
+	#[seeded(plain())]
+	enabled: bool,
+}
+
+fn error(json: &str) -> String {
+	Config::seed(10)
+		.deserialize(&mut serde_json::Deserializer::from_str(json))
+		.unwrap_err()
+		.to_string()
+}
+
+#[test]
+fn any_order() {
+	let expected = Config {
+		name: "name".to_owned(),
+		size: 30,
+		enabled: true,
+	};
+	for json in &[
+		r#"{"name":"name","size":3,"enabled":true}"#,
+		r#"{"enabled":true,"size":3,"name":"name"}"#,
+		r#"{"size":3,"name":"name","enabled":true}"#,
+	] {
+		assert_eq!(from_json(Config::seed(10), json), expected);
+	}
+}
+
+#[test]
+fn duplicate() {
+	assert_eq!(
+		error(r#"{"name":"name","size":3,"name":"other","enabled":true}"#),
+		"duplicate field `name` at line 1 column 30",
+	);
+}
+
+#[test]
+fn missing() {
+	assert_eq!(
+		error(r#"{"name":"name","enabled":true}"#),
+		"missing field `size` at line 1 column 30",
+	);
+	assert_eq!(error("{}"), "missing field `name` at line 1 column 2");
+}
+
+/// Not [`Clone`], so the seeder below can only consume it once.
+struct Prefix(String);
+
+struct Prefixed(Prefix);
+
+impl<'de> DeSeeder<'de, String> for Prefixed {
+	type Seed = Self;
+	fn seed(self) -> Self::Seed {
+		self
+	}
+}
+
+impl<'de> DeserializeSeed<'de> for Prefixed {
+	type Value = String;
+	fn deserialize<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<String, D::Error> {
+		let Prefixed(Prefix(mut prefix)) = self;
+		prefix.push_str(&String::deserialize(deserializer)?);
+		Ok(prefix)
+	}
+}
+
+#[derive(Debug, PartialEq, seed)]
+#[seed_args(prefix: Prefix)]
+struct Named {
+	#[seeded(Prefixed(prefix))]
+	name: String,
+	id: u32,
+}
+
+#[test]
+fn consumed_argument() {
+	let prefix = || Prefix("a/".to_owned());
+	let expected = Named {
+		name: "a/b".to_owned(),
+		id: 1,
+	};
+	assert_eq!(
+		from_json(Named::seed(prefix()), r#"{"id":1,"name":"b"}"#),
+		expected
+	);
+	assert_eq!(from_json(Named::seed(prefix()), r#"["b",1]"#), expected);
+
+	// The seed is built once, so a second key is a duplicate even though its argument is used up.
+	let error = Named::seed(prefix())
+		.deserialize(&mut serde_json::Deserializer::from_str(
+			r#"{"name":"b","name":"c","id":1}"#,
+		))
+		.unwrap_err();
+	assert_eq!(
+		error.to_string(),
+		"duplicate field `name` at line 1 column 18"
+	);
+}
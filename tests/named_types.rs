@@ -0,0 +1,124 @@
+//! Named seed and view types, which can be stored, cloned where their arguments allow it, and debug-printed.
+
+use serde::de::DeserializeSeed;
+use serde_seeded::{seed, seeded};
+use std::marker::PhantomData;
+
+mod common;
+use common::*;
+
+#[derive(Debug, PartialEq, seed, seeded)]
+#[seed_type(pub PointSeed)]
+#[seeded_type(pub PointSeeded)]
+#[seed_args(scale: u32)]
+pub struct Point {
+	#[seeded_de(Scaled(&scale))]
+	#[seeded_ser(Scaled(scale))]
+	x: u32,
+	y: u32,
+}
+
+/// Keeps a seed around to read several values with it.
+struct Reader {
+	seed: PointSeed,
+}
+
+impl Reader {
+	fn new(scale: u32) -> Self {
+		Self {
+			seed: Point::seed(scale),
+		}
+	}
+
+	fn read(&self, json: &str) -> Point {
+		from_json(self.seed.clone(), json)
+	}
+}
+
+#[test]
+fn stored() {
+	let reader = Reader::new(10);
+	assert_eq!(reader.read(r#"{"x":1,"y":2}"#), Point { x: 10, y: 2 });
+	assert_eq!(reader.read(r#"{"x":3,"y":4}"#), Point { x: 30, y: 4 });
+	assert_eq!(format!("{:?}", reader.seed), "PointSeed { .. }");
+
+	let point = Point { x: 50, y: 6 };
+	let views: Vec<PointSeeded<'_>> = vec![point.seeded(10); 2];
+	assert_eq!(to_json(&views), r#"[{"x":5,"y":6},{"x":5,"y":6}]"#);
+	assert_eq!(format!("{:?}", views[0]), "PointSeeded { .. }");
+}
+
+#[derive(Debug, PartialEq, seed, seeded)]
+#[seed_type(LogSeed)]
+#[seeded_type(LogSeeded)]
+#[seed_args_de(_log: Vec<String>)]
+#[seed_args_ser(_prefix: String)]
+#[seed_where_de(T: serde::Deserialize<'de>)]
+#[seed_where_ser(T: serde::Serialize)]
+enum Logged<T> {
+	Entry(T),
+}
+
+/// Owned by the seeds and views below, which therefore can't be cloned either.
+#[derive(Debug)]
+struct Factor(u32);
+
+#[derive(Debug, PartialEq, seed, seeded)]
+#[seed_type(ProductSeed)]
+#[seeded_type(ProductSeeded)]
+#[seed_args(factor: Factor)]
+struct Product {
+	#[seeded(Scaled(&factor.0))]
+	value: u32,
+}
+
+/// Whether `T` implements [`Clone`], through autoref-based method resolution,
+/// so that this also compiles if it doesn't.
+struct Probe<T>(PhantomData<T>);
+
+trait IsClone {
+	fn is_clone(&self) -> bool {
+		true
+	}
+}
+impl<T: Clone> IsClone for Probe<T> {}
+
+trait NotClone {
+	fn is_clone(&self) -> bool {
+		false
+	}
+}
+impl<T> NotClone for &Probe<T> {}
+
+macro_rules! is_clone {
+	($ty:ty) => {
+		(&Probe::<$ty>(PhantomData)).is_clone()
+	};
+}
+
+#[test]
+fn clone_only_where_possible() {
+	assert!(is_clone!(PointSeed));
+	assert!(is_clone!(PointSeeded<'_>));
+	assert!(is_clone!(LogSeed<u8>));
+	assert!(is_clone!(LogSeeded<'_, u8>));
+	// An owned argument without `Clone` can't be cloned, so neither can the seed or view that holds it.
+	assert!(!is_clone!(ProductSeed));
+	assert!(!is_clone!(ProductSeeded<'_>));
+
+	let seed: LogSeed<u8> = Logged::seed(vec![]);
+	assert_eq!(format!("{:?}", seed), "LogSeed { .. }");
+	let entry = seed
+		.deserialize(&mut serde_json::Deserializer::from_str(r#"{"Entry":1}"#))
+		.unwrap();
+	assert_eq!(entry, Logged::Entry(1));
+
+	let view: LogSeeded<'_, u8> = entry.seeded("log".to_owned());
+	assert_eq!(format!("{:?}", view.clone()), "LogSeeded { .. }");
+	assert_eq!(to_json(view), r#"{"Entry":1}"#);
+
+	let product = Product { value: 30 };
+	let json = to_json(product.seeded(Factor(10)));
+	assert_eq!(json, r#"{"value":3}"#);
+	assert_eq!(from_json(Product::seed(Factor(10)), &json), product);
+}
@@ -0,0 +1,51 @@
+//! Fields without a seeder or with `#[seeded(plain)]`, which go through their own serde implementations.
+
+use serde_seeded::{seed, seeded};
+
+mod common;
+use common::*;
+
+/// The view lends its arguments, so `scale` is a reference there.
+#[derive(Debug, PartialEq, seed, seeded)]
+#[seed_args(scale: u32)]
+struct Item {
+	name: String,
+	#[seeded(plain)]
+	count: u32,
+	#[seeded_de(Scaled(&scale))]
+	#[seeded_ser(Scaled(scale))]
+	size: u32,
+}
+
+/// Needs `T: Deserialize<'de>` and `T: Serialize`, which are inferred like serde_derive infers them.
+#[derive(Debug, PartialEq, seed, seeded)]
+struct Wrapper<T> {
+	value: T,
+	tags: Vec<T>,
+}
+
+#[test]
+fn round_trip() {
+	let item = Item {
+		name: "name".to_owned(),
+		count: 2,
+		size: 30,
+	};
+	let json = to_json(item.seeded(10));
+	assert_eq!(json, r#"{"name":"name","count":2,"size":3}"#);
+	assert_eq!(from_json(Item::seed(10), &json), item);
+
+	let bytes = to_bincode(item.seeded(10));
+	assert_eq!(from_bincode(Item::seed(10), &bytes), item);
+}
+
+#[test]
+fn generic() {
+	let wrapper = Wrapper {
+		value: 1_u8,
+		tags: vec![2, 3],
+	};
+	let json = to_json(wrapper.seeded());
+	assert_eq!(json, r#"{"value":1,"tags":[2,3]}"#);
+	assert_eq!(from_json(Wrapper::seed(), &json), wrapper);
+}
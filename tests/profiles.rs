@@ -0,0 +1,139 @@
+//! Round trips through the methods of each `seed_profile`, with their own arguments and field seeders.
+//!
+//! Types with profiles only get the profiles' methods,
+//! and implement `SeedWith` and `SeededWith` once for each profile with its own argument types.
+
+use serde::de::{self, DeserializeSeed};
+use serde_seeded::{seed, seeded, DeSeeder, SeedWith, Seeded, SeededWith, SerSeeder};
+
+mod common;
+use common::*;
+
+/// Writes values less the offset and adds it back when reading them.
+struct Offset<'a>(&'a u32);
+
+impl<'de> DeSeeder<'de, u32> for Offset<'_> {
+	type Seed = Self;
+	fn seed(self) -> Self::Seed {
+		self
+	}
+}
+
+impl<'de> DeserializeSeed<'de> for Offset<'_> {
+	type Value = u32;
+	fn deserialize<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<u32, D::Error> {
+		Ok(<u32 as de::Deserialize>::deserialize(deserializer)? + self.0)
+	}
+}
+
+impl SerSeeder<u32> for Offset<'_> {
+	fn seeded<'s>(&'s self, value: &'s u32) -> Seeded<'s> {
+		Box::new(value - self.0)
+	}
+}
+
+#[derive(Debug, PartialEq, seed, seeded)]
+#[seed_args(scale: &u32)]
+#[seed_profile(net, args(offset: &u32))]
+#[seed_profile(disk)]
+struct Stats {
+	#[seeded(Scaled(scale), net = Offset(offset))]
+	health: u32,
+	#[seeded(Scaled(scale), net = plain)]
+	mana: u32,
+	name: String,
+}
+
+#[test]
+fn profiles() {
+	let stats = Stats {
+		health: 150,
+		mana: 20,
+		name: "name".to_owned(),
+	};
+
+	let json = to_json(stats.seeded_net(&10, &100));
+	assert_eq!(json, r#"{"health":50,"mana":20,"name":"name"}"#);
+	assert_eq!(from_json(Stats::seed_net(&10, &100), &json), stats);
+	let bytes = to_bincode(stats.seeded_net(&10, &100));
+	assert_eq!(from_bincode(Stats::seed_net(&10, &100), &bytes), stats);
+
+	// Without seeders of its own, a profile uses the ones given without a profile name.
+	let json = to_json(stats.seeded_disk(&10));
+	assert_eq!(json, r#"{"health":15,"mana":2,"name":"name"}"#);
+	assert_eq!(from_json(Stats::seed_disk(&10), &json), stats);
+	let bytes = to_bincode(stats.seeded_disk(&10));
+	assert_eq!(from_bincode(Stats::seed_disk(&10), &bytes), stats);
+}
+
+#[derive(Debug, PartialEq, seed, seeded)]
+#[seed_fn(load)]
+#[seeded_fn(save)]
+#[seed_profile(net, args(offset: &u32))]
+#[seed_profile(disk)]
+struct Id(#[seeded(net = Offset(offset))] u32);
+
+#[test]
+fn renamed() {
+	// Profile names are appended to custom method names too.
+	let id = Id(105);
+	assert_eq!(to_json(id.save_disk()), "105");
+	assert_eq!(from_json(Id::load_disk(), "105"), id);
+	assert_eq!(to_json(id.save_net(&100)), "5");
+	assert_eq!(from_json(Id::load_net(&100), "5"), id);
+}
+
+#[test]
+fn traits() {
+	let stats = Stats {
+		health: 150,
+		mana: 20,
+		name: "name".to_owned(),
+	};
+
+	// The arguments pick the profile.
+	let json = to_json(stats.seeded_with((&10, &100)));
+	assert_eq!(json, to_json(stats.seeded_net(&10, &100)));
+	assert_eq!(from_json(Stats::seed_with((&10, &100)), &json), stats);
+	let json = to_json(stats.seeded_with((&10,)));
+	assert_eq!(json, to_json(stats.seeded_disk(&10)));
+	assert_eq!(from_json(Stats::seed_with((&10,)), &json), stats);
+}
+
+#[derive(Debug, PartialEq, seed, seeded)]
+#[seed_args(scale: &u32)]
+struct Player {
+	#[seeded(forward)]
+	stats: Stats,
+}
+
+#[test]
+fn forward() {
+	let player = Player {
+		stats: Stats {
+			health: 150,
+			mana: 20,
+			name: "name".to_owned(),
+		},
+	};
+	let json = to_json(player.seeded(&10));
+	assert_eq!(json, r#"{"stats":{"health":15,"mana":2,"name":"name"}}"#);
+	assert_eq!(from_json(Player::seed(&10), &json), player);
+}
+
+/// Its profiles take the same arguments, so neither implements the traits.
+#[derive(Debug, PartialEq, seed, seeded)]
+#[seed_profile(plain)]
+#[seed_profile(offset, args(offset: &'static u32))]
+#[seed_profile(shifted, args(shift: &u32))]
+struct Level(#[seeded(offset = Offset(offset), shifted = Offset(shift))] u32);
+
+#[test]
+fn shared_argument_types() {
+	let level = Level(105);
+	assert_eq!(to_json(level.seeded_offset(&100)), "5");
+	assert_eq!(from_json(Level::seed_shifted(&100), "5"), level);
+	// The profile with distinct arguments still implements them.
+	assert_eq!(to_json(level.seeded_with(())), "105");
+	assert_eq!(from_json(Level::seed_with(()), "105"), level);
+}
@@ -0,0 +1,67 @@
+//! Mutable reference seed arguments, which each field seeder reborrows in turn.
+
+use serde::de::{self, DeserializeSeed};
+use serde_seeded::{seed, DeSeeder};
+
+mod common;
+use common::*;
+
+/// Counts the values it reads.
+struct Counted<'a>(&'a mut u32);
+
+impl<'de> DeSeeder<'de, u32> for Counted<'_> {
+	type Seed = Self;
+	fn seed(self) -> Self::Seed {
+		self
+	}
+}
+
+impl<'de> DeserializeSeed<'de> for Counted<'_> {
+	type Value = u32;
+	fn deserialize<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<u32, D::Error> {
+		*self.0 += 1;
+		<u32 as de::Deserialize>::deserialize(deserializer)
+	}
+}
+
+#[derive(Debug, PartialEq, seed)]
+#[seed_args(counter: &mut u32)]
+struct Inner {
+	#[seeded(Counted(counter))]
+	c: u32,
+}
+
+#[derive(Debug, PartialEq, seed)]
+#[seed_args(counter: &mut u32)]
+struct Outer {
+	#[seeded(Counted(counter))]
+	a: u32,
+	#[seeded(Counted(counter))]
+	b: u32,
+	#[seeded(forward)]
+	inner: Inner,
+}
+
+#[test]
+fn shared() {
+	let expected = Outer {
+		a: 1,
+		b: 2,
+		inner: Inner { c: 3 },
+	};
+
+	let mut counter = 0;
+	let outer = from_json(
+		Outer::seed(&mut counter),
+		r#"{"a":1,"b":2,"inner":{"c":3}}"#,
+	);
+	assert_eq!(outer, expected);
+	assert_eq!(counter, 3);
+
+	let outer = from_bincode(
+		Outer::seed(&mut counter),
+		&to_bincode((1_u32, 2_u32, (3_u32,))),
+	);
+	assert_eq!(outer, expected);
+	assert_eq!(counter, 6);
+}
@@ -0,0 +1,115 @@
+//! Field and variant names: renames, aliases and `seed_rename_all`, whose rules have to match serde's.
+
+use serde::{de::DeserializeSeed, Deserialize, Serialize};
+use serde_seeded::{seed, seeded};
+
+mod common;
+use common::*;
+
+#[derive(Debug, PartialEq, seed, seeded)]
+#[seed_rename_all = "camelCase"]
+struct Api {
+	user_id: u32,
+	#[seeded(rename = "URL")]
+	home_url: String,
+	#[seeded_de(rename = "name", alias = "display_name", alias = "title")]
+	#[seeded_ser(rename = "displayName")]
+	display_name: String,
+	r#type: u8,
+}
+
+#[test]
+fn renames() {
+	let api = Api {
+		user_id: 1,
+		home_url: "url".to_owned(),
+		display_name: "name".to_owned(),
+		r#type: 2,
+	};
+
+	let json = to_json(api.seeded());
+	assert_eq!(
+		json,
+		r#"{"userId":1,"URL":"url","displayName":"name","type":2}"#,
+	);
+	for json in [
+		r#"{"userId":1,"URL":"url","name":"name","type":2}"#,
+		r#"{"userId":1,"URL":"url","display_name":"name","type":2}"#,
+		r#"{"userId":1,"URL":"url","title":"name","type":2}"#,
+	]
+	.iter()
+	{
+		assert_eq!(from_json(Api::seed(), json), api);
+	}
+
+	// Aliases are the same field, and the serialized name is only an alias if it's declared as one.
+	let error = |json: &str| {
+		Api::seed()
+			.deserialize(&mut serde_json::Deserializer::from_str(json))
+			.unwrap_err()
+			.to_string()
+	};
+	assert_eq!(
+		error(r#"{"userId":1,"URL":"url","name":"a","title":"b","type":2}"#),
+		"duplicate field `name` at line 1 column 42",
+	);
+	assert_eq!(
+		error(r#"{"userId":1,"URL":"url","displayName":"name","type":2}"#),
+		"missing field `name` at line 1 column 54",
+	);
+}
+
+macro_rules! rename_all {
+	($($module:ident: $rule:literal => $field:literal, $variant:literal;)*) => {$(
+		mod $module {
+			use super::*;
+
+			#[derive(Debug, PartialEq, Serialize, Deserialize, seed, seeded)]
+			#[serde(rename_all = $rule)]
+			#[seed_rename_all = $rule]
+			pub struct Struct {
+				first: u8,
+				second_field_name: u8,
+			}
+
+			#[derive(Debug, PartialEq, Serialize, Deserialize, seed, seeded)]
+			#[serde(rename_all = $rule)]
+			#[seed_rename_all = $rule]
+			pub enum Enum {
+				First,
+				SecondVariantName { some_field: u8 },
+			}
+
+			#[test]
+			fn like_serde() {
+				let value = Struct {
+					first: 1,
+					second_field_name: 2,
+				};
+				let json = to_json(value.seeded());
+				assert_eq!(json, to_json(&value));
+				assert!(json.contains(concat!('"', $field, '"')));
+				assert_eq!(from_json(Struct::seed(), &json), value);
+
+				for value in vec![Enum::First, Enum::SecondVariantName { some_field: 3 }] {
+					let json = to_json(value.seeded());
+					assert_eq!(json, to_json(&value));
+					assert_eq!(from_json(Enum::seed(), &json), value);
+				}
+				assert!(to_json(Enum::SecondVariantName { some_field: 3 }.seeded())
+					.contains(concat!('"', $variant, '"')));
+			}
+		}
+	)*};
+}
+
+rename_all! {
+	lower: "lowercase" => "second_field_name", "secondvariantname";
+	upper: "UPPERCASE" => "SECOND_FIELD_NAME", "SECONDVARIANTNAME";
+	pascal: "PascalCase" => "SecondFieldName", "SecondVariantName";
+	camel: "camelCase" => "secondFieldName", "secondVariantName";
+	snake: "snake_case" => "second_field_name", "second_variant_name";
+	screaming_snake: "SCREAMING_SNAKE_CASE" => "SECOND_FIELD_NAME", "SECOND_VARIANT_NAME";
+	kebab: "kebab-case" => "second-field-name", "second-variant-name";
+	screaming_kebab: "SCREAMING-KEBAB-CASE" => "SECOND-FIELD-NAME", "SECOND-VARIANT-NAME";
+}
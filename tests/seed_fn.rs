@@ -0,0 +1,48 @@
+//! Derived methods with custom names and visibility, which leave the default names free for the type's own use.
+
+use serde_seeded::{SeedWith, SeededWith};
+
+mod common;
+use common::*;
+
+mod entity {
+	use super::*;
+	use serde_seeded::{seed, seeded};
+
+	#[derive(Debug, PartialEq, seed, seeded)]
+	#[seed_fn(pub(crate) load_with)]
+	#[seeded_fn(pub(crate) save_with)]
+	#[seed_args(scale: u32)]
+	pub struct Entity {
+		#[seeded_de(Scaled(&scale))]
+		#[seeded_ser(Scaled(scale))]
+		pub size: u32,
+		pub seed: u64,
+	}
+
+	impl Entity {
+		pub fn seed(&self) -> u64 {
+			self.seed
+		}
+
+		pub fn seeded(&self) -> bool {
+			self.seed != 0
+		}
+	}
+}
+use entity::Entity;
+
+#[test]
+fn renamed() {
+	let entity = Entity { size: 20, seed: 7 };
+	assert_eq!(entity.seed(), 7);
+	assert!(entity.seeded());
+
+	let json = to_json(entity.save_with(10));
+	assert_eq!(json, r#"{"size":2,"seed":7}"#);
+	assert_eq!(from_json(Entity::load_with(10), &json), entity);
+
+	// The traits call the renamed methods.
+	assert_eq!(to_json(entity.seeded_with((10,))), json);
+	assert_eq!(from_json(Entity::seed_with((10,)), &json), entity);
+}
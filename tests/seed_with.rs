@@ -0,0 +1,140 @@
+//! Generic code over derived types through `SeedWith` and `SeededWith`, with the seed arguments as tuple.
+
+use serde_seeded::{seed, seeded, SeedWith, SeededWith};
+
+mod common;
+use common::*;
+
+#[derive(Debug, PartialEq, seed, seeded)]
+#[seed_args(scale: u32)]
+struct Point {
+	#[seeded_de(Scaled(&scale))]
+	#[seeded_ser(Scaled(scale))]
+	x: u32,
+	y: u32,
+}
+
+#[derive(Debug, PartialEq, seed, seeded)]
+#[seed_args(scale: u32)]
+enum Size {
+	Square(
+		#[seeded_de(Scaled(&scale))]
+		#[seeded_ser(Scaled(scale))]
+		u32,
+	),
+	Rectangle {
+		#[seeded_de(Scaled(&scale))]
+		#[seeded_ser(Scaled(scale))]
+		width: u32,
+		#[seeded_de(Scaled(&scale))]
+		#[seeded_ser(Scaled(scale))]
+		height: u32,
+	},
+}
+
+#[derive(Debug, PartialEq, seed, seeded)]
+struct Name(String);
+
+fn load<'de, T: SeedWith<'de, (u32,)>>(scale: u32, json: &'de str) -> T {
+	from_json(T::seed_with((scale,)), json)
+}
+
+fn save<'ser, T: SeededWith<'ser, (u32,)>>(value: &'ser T, scale: u32) -> String {
+	to_json(value.seeded_with((scale,)))
+}
+
+#[test]
+fn generic() {
+	let point = Point { x: 40, y: 2 };
+	let json = save(&point, 10);
+	assert_eq!(json, r#"{"x":4,"y":2}"#);
+	assert_eq!(load::<Point>(10, &json), point);
+
+	let size = Size::Rectangle {
+		width: 30,
+		height: 50,
+	};
+	let json = save(&size, 10);
+	assert_eq!(json, r#"{"Rectangle":{"width":3,"height":5}}"#);
+	assert_eq!(load::<Size>(10, &json), size);
+	assert_eq!(load::<Size>(10, r#"{"Square":2}"#), Size::Square(20));
+}
+
+#[test]
+fn without_arguments() {
+	let name = Name("name".to_owned());
+	let bytes = to_bincode(name.seeded_with(()));
+	assert_eq!(from_bincode(Name::seed_with(()), &bytes), name);
+}
+
+#[derive(Debug, PartialEq, seed, seeded)]
+#[seed_args(offset: &u32, scale: &u32)]
+struct Range {
+	#[seeded(Scaled(scale))]
+	start: u32,
+	#[seeded(Scaled(offset))]
+	end: u32,
+}
+
+/// Forwards only some of its arguments, and in another order.
+#[derive(Debug, PartialEq, seed, seeded)]
+#[seed_args(scale: &u32, _unused: &str, offset: &u32)]
+struct Span {
+	#[seeded(forward(offset, scale))]
+	range: Range,
+	#[seeded(forward(scale))]
+	point: Point,
+}
+
+#[test]
+fn forward_subset() {
+	let span = Span {
+		range: Range {
+			start: 20,
+			end: 300,
+		},
+		point: Point { x: 40, y: 2 },
+	};
+	let json = to_json(span.seeded(&10, "unused", &100));
+	assert_eq!(
+		json,
+		r#"{"range":{"start":2,"end":3},"point":{"x":4,"y":2}}"#
+	);
+	assert_eq!(from_json(Span::seed(&10, "unused", &100), &json), span);
+
+	let bytes = to_bincode(span.seeded(&10, "unused", &100));
+	assert_eq!(bytes, to_bincode(((2_u32, 3_u32), (4_u32, 2_u32))));
+	assert_eq!(from_bincode(Span::seed(&10, "unused", &100), &bytes), span);
+}
+
+mod renamed {
+	use super::*;
+
+	#[derive(Debug, PartialEq, seed, seeded)]
+	#[seed_fn(pub(crate) load_with)]
+	#[seeded_fn(pub(crate) save_with)]
+	#[seed_args(scale: &u32)]
+	pub struct Renamed {
+		#[seeded(Scaled(scale))]
+		pub x: u32,
+	}
+}
+use renamed::Renamed;
+
+/// Forwards to a type whose derived methods have other names.
+#[derive(Debug, PartialEq, seed, seeded)]
+#[seed_args(scale: &u32)]
+struct Wrapper {
+	#[seeded(forward)]
+	inner: Renamed,
+}
+
+#[test]
+fn forward_renamed() {
+	let wrapper = Wrapper {
+		inner: Renamed { x: 30 },
+	};
+	let json = to_json(wrapper.seeded(&10));
+	assert_eq!(json, r#"{"inner":{"x":3}}"#);
+	assert_eq!(from_json(Wrapper::seed(&10), &json), wrapper);
+}
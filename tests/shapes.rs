@@ -0,0 +1,125 @@
+//! Newtype, tuple and unit structs and enum variants, which are written like serde_derive writes the same shapes.
+
+use serde::{Deserialize, Serialize};
+use serde_seeded::{seed, seeded};
+
+mod common;
+use common::*;
+
+/// Checks that `$ours` is written like serde_derive writes `$theirs`, and read back from that.
+macro_rules! same_as_serde {
+	($ty:ident, $ours:expr, $theirs:expr) => {{
+		let ours = $ours;
+		let json = serde_json::to_string(&$theirs).unwrap();
+		assert_eq!(to_json(ours.seeded(10)), json);
+		assert_eq!(from_json($ty::seed(10), &json), ours);
+
+		let bytes = to_bincode(&$theirs);
+		assert_eq!(to_bincode(ours.seeded(10)), bytes);
+		assert_eq!(from_bincode($ty::seed(10), &bytes), ours);
+	}};
+}
+
+/// The views lend their arguments, so `scale` is a reference there.
+#[derive(Debug, PartialEq, seed, seeded)]
+#[seed_args(scale: u32)]
+struct Meters(
+	#[seeded_de(Scaled(&scale))]
+	#[seeded_ser(Scaled(scale))]
+	u32,
+);
+
+#[derive(Serialize, Deserialize)]
+struct PlainMeters(u32);
+
+#[derive(Debug, PartialEq, seed, seeded)]
+#[seed_args(scale: u32)]
+struct Pair(
+	#[seeded_de(Scaled(&scale))]
+	#[seeded_ser(Scaled(scale))]
+	u32,
+	#[seeded(plain())] String,
+);
+
+#[derive(Serialize, Deserialize)]
+struct PlainPair(u32, String);
+
+#[derive(Debug, PartialEq, seed, seeded)]
+#[seed_args(_scale: u32)]
+struct Marker;
+
+#[derive(Serialize, Deserialize)]
+struct PlainMarker;
+
+#[test]
+fn newtype() {
+	same_as_serde!(Meters, Meters(30), PlainMeters(3));
+}
+
+#[test]
+fn tuple() {
+	same_as_serde!(
+		Pair,
+		Pair(30, "pair".to_owned()),
+		PlainPair(3, "pair".to_owned())
+	);
+}
+
+#[test]
+fn unit() {
+	same_as_serde!(Marker, Marker, PlainMarker);
+}
+
+#[derive(Debug, PartialEq, seed, seeded)]
+#[seed_args(scale: u32)]
+enum Shape {
+	Unit,
+	Newtype(
+		#[seeded_de(Scaled(&scale))]
+		#[seeded_ser(Scaled(scale))]
+		u32,
+	),
+	Tuple(
+		#[seeded_de(Scaled(&scale))]
+		#[seeded_ser(Scaled(scale))]
+		u32,
+		#[seeded(plain())] String,
+	),
+	Struct {
+		#[seeded_de(Scaled(&scale))]
+		#[seeded_ser(Scaled(scale))]
+		size: u32,
+		#[seeded(plain())]
+		name: String,
+	},
+}
+
+#[derive(Serialize, Deserialize)]
+enum PlainShape {
+	Unit,
+	Newtype(u32),
+	Tuple(u32, String),
+	Struct { size: u32, name: String },
+}
+
+#[test]
+fn variants() {
+	same_as_serde!(Shape, Shape::Unit, PlainShape::Unit);
+	same_as_serde!(Shape, Shape::Newtype(30), PlainShape::Newtype(3));
+	same_as_serde!(
+		Shape,
+		Shape::Tuple(30, "tuple".to_owned()),
+		PlainShape::Tuple(3, "tuple".to_owned())
+	);
+	same_as_serde!(
+		Shape,
+		Shape::Struct {
+			size: 30,
+			name: "struct".to_owned(),
+		},
+		PlainShape::Struct {
+			size: 3,
+			name: "struct".to_owned(),
+		}
+	);
+}
@@ -0,0 +1,361 @@
+//! Round trips of types with skipped fields, and what serializers are told about them.
+
+use serde::ser::{self, Serialize};
+use serde_seeded::{seed, seeded};
+use std::fmt;
+
+mod common;
+use common::*;
+
+#[derive(Debug, PartialEq)]
+struct Handle(u32);
+
+struct Runtime;
+
+impl Runtime {
+	fn handle(&self) -> Handle {
+		Handle(42)
+	}
+}
+
+#[derive(Debug, PartialEq, seed, seeded)]
+#[seed_args_de(runtime: Runtime)]
+#[seed_args_ser(hide_empty: bool)]
+struct Cached {
+	id: u32,
+	#[seeded(skip)]
+	cache: Vec<u8>,
+	#[seeded_de(skip = runtime.handle())]
+	#[seeded_ser(skip)]
+	handle: Handle,
+	#[seeded_de(default)]
+	#[seeded_ser(skip_if = *hide_empty && items.is_empty())]
+	items: Vec<u8>,
+}
+
+#[derive(Debug, PartialEq, seed, seeded)]
+struct Triple(u8, #[seeded(skip)] u8, u8);
+
+#[derive(Debug, PartialEq, seed, seeded)]
+enum Shape {
+	Tuple(u8, #[seeded(skip)] u8, u8),
+	Struct {
+		#[seeded(skip, default = 7)]
+		x: u8,
+		#[seeded_ser(skip_if = y.is_none())]
+		y: Option<u8>,
+	},
+}
+
+#[test]
+fn round_trip() {
+	let cached = Cached {
+		id: 1,
+		cache: vec![1, 2, 3],
+		handle: Handle(1),
+		items: vec![],
+	};
+	let read = Cached {
+		id: 1,
+		cache: vec![],
+		handle: Handle(42),
+		items: vec![],
+	};
+
+	let json = to_json(cached.seeded(true));
+	assert_eq!(json, r#"{"id":1}"#);
+	assert_eq!(from_json(Cached::seed(Runtime), &json), read);
+	let json = to_json(cached.seeded(false));
+	assert_eq!(json, r#"{"id":1,"items":[]}"#);
+	assert_eq!(from_json(Cached::seed(Runtime), &json), read);
+
+	// Conditionally skipped fields can't be left out of sequences, but always skipped ones are.
+	let bytes = to_bincode(cached.seeded(false));
+	assert_eq!(bytes, to_bincode((1_u32, Vec::<u8>::new())));
+	assert_eq!(from_bincode(Cached::seed(Runtime), &bytes), read);
+}
+
+#[test]
+fn shapes() {
+	let triple = Triple(1, 2, 3);
+	let json = to_json(triple.seeded());
+	assert_eq!(json, "[1,3]");
+	assert_eq!(from_json(Triple::seed(), &json), Triple(1, 0, 3));
+
+	let tuple = Shape::Tuple(1, 2, 3);
+	let json = to_json(tuple.seeded());
+	assert_eq!(json, r#"{"Tuple":[1,3]}"#);
+	assert_eq!(from_json(Shape::seed(), &json), Shape::Tuple(1, 0, 3));
+
+	let without = Shape::Struct { x: 1, y: None };
+	let json = to_json(without.seeded());
+	assert_eq!(json, r#"{"Struct":{}}"#);
+	assert_eq!(
+		from_json(Shape::seed(), &json),
+		Shape::Struct { x: 7, y: None },
+	);
+
+	let with = Shape::Struct { x: 1, y: Some(2) };
+	let json = to_json(with.seeded());
+	assert_eq!(json, r#"{"Struct":{"y":2}}"#);
+	assert_eq!(
+		from_json(Shape::seed(), &json),
+		Shape::Struct { x: 7, y: Some(2) },
+	);
+}
+
+#[test]
+fn lengths() {
+	let cached = Cached {
+		id: 1,
+		cache: vec![],
+		handle: Handle(1),
+		items: vec![],
+	};
+	assert_eq!(
+		record(cached.seeded(true)),
+		[
+			"struct Cached 1",
+			"field id",
+			"skip cache",
+			"skip handle",
+			"skip items",
+			"end",
+		],
+	);
+	assert_eq!(
+		record(cached.seeded(false)),
+		[
+			"struct Cached 2",
+			"field id",
+			"skip cache",
+			"skip handle",
+			"field items",
+			"end",
+		],
+	);
+
+	assert_eq!(
+		record(Triple(1, 2, 3).seeded()),
+		["tuple struct Triple 2", "field", "field", "end"],
+	);
+	assert_eq!(
+		record(Shape::Tuple(1, 2, 3).seeded()),
+		["tuple variant Shape::Tuple 2", "field", "field", "end"],
+	);
+	assert_eq!(
+		record(Shape::Struct { x: 1, y: None }.seeded()),
+		["struct variant Shape::Struct 0", "skip x", "skip y", "end"],
+	);
+}
+
+/// Lists which compound serializers are started with which length, and what happens to their fields.
+fn record(value: impl Serialize) -> Vec<String> {
+	let mut recorder = Recorder(vec![]);
+	value.serialize(&mut recorder).unwrap();
+	recorder.0
+}
+
+struct Recorder(Vec<String>);
+
+#[derive(Debug)]
+struct Unsupported;
+
+impl fmt::Display for Unsupported {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("unsupported")
+	}
+}
+
+impl std::error::Error for Unsupported {}
+
+impl ser::Error for Unsupported {
+	fn custom<T: fmt::Display>(_: T) -> Self {
+		Unsupported
+	}
+}
+
+macro_rules! unsupported {
+	($($method:ident($($ty:ty),*)),*$(,)?) => {$(
+		fn $method(self, $(_: $ty),*) -> Result<(), Unsupported> {
+			Err(Unsupported)
+		}
+	)*};
+}
+
+impl ser::Serializer for &mut Recorder {
+	type Ok = ();
+	type Error = Unsupported;
+	type SerializeSeq = ser::Impossible<(), Unsupported>;
+	type SerializeTuple = ser::Impossible<(), Unsupported>;
+	type SerializeTupleStruct = Self;
+	type SerializeTupleVariant = Self;
+	type SerializeMap = ser::Impossible<(), Unsupported>;
+	type SerializeStruct = Self;
+	type SerializeStructVariant = Self;
+
+	unsupported! {
+		serialize_bool(bool),
+		serialize_i8(i8),
+		serialize_i16(i16),
+		serialize_i32(i32),
+		serialize_i64(i64),
+		serialize_u8(u8),
+		serialize_u16(u16),
+		serialize_u32(u32),
+		serialize_u64(u64),
+		serialize_f32(f32),
+		serialize_f64(f64),
+		serialize_char(char),
+		serialize_str(&str),
+		serialize_bytes(&[u8]),
+		serialize_none(),
+		serialize_unit(),
+		serialize_unit_struct(&'static str),
+		serialize_unit_variant(&'static str, u32, &'static str),
+	}
+
+	fn serialize_some<T: ?Sized + Serialize>(self, _: &T) -> Result<(), Unsupported> {
+		Err(Unsupported)
+	}
+
+	fn serialize_newtype_struct<T: ?Sized + Serialize>(
+		self,
+		_: &'static str,
+		_: &T,
+	) -> Result<(), Unsupported> {
+		Err(Unsupported)
+	}
+
+	fn serialize_newtype_variant<T: ?Sized + Serialize>(
+		self,
+		_: &'static str,
+		_: u32,
+		_: &'static str,
+		_: &T,
+	) -> Result<(), Unsupported> {
+		Err(Unsupported)
+	}
+
+	fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, Unsupported> {
+		Err(Unsupported)
+	}
+
+	fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple, Unsupported> {
+		Err(Unsupported)
+	}
+
+	fn serialize_tuple_struct(
+		self,
+		name: &'static str,
+		len: usize,
+	) -> Result<Self::SerializeTupleStruct, Unsupported> {
+		self.0.push(format!("tuple struct {} {}", name, len));
+		Ok(self)
+	}
+
+	fn serialize_tuple_variant(
+		self,
+		name: &'static str,
+		_: u32,
+		variant: &'static str,
+		len: usize,
+	) -> Result<Self::SerializeTupleVariant, Unsupported> {
+		self.0
+			.push(format!("tuple variant {}::{} {}", name, variant, len));
+		Ok(self)
+	}
+
+	fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, Unsupported> {
+		Err(Unsupported)
+	}
+
+	fn serialize_struct(
+		self,
+		name: &'static str,
+		len: usize,
+	) -> Result<Self::SerializeStruct, Unsupported> {
+		self.0.push(format!("struct {} {}", name, len));
+		Ok(self)
+	}
+
+	fn serialize_struct_variant(
+		self,
+		name: &'static str,
+		_: u32,
+		variant: &'static str,
+		len: usize,
+	) -> Result<Self::SerializeStructVariant, Unsupported> {
+		self.0
+			.push(format!("struct variant {}::{} {}", name, variant, len));
+		Ok(self)
+	}
+}
+
+impl ser::SerializeTupleStruct for &mut Recorder {
+	type Ok = ();
+	type Error = Unsupported;
+	fn serialize_field<T: ?Sized + Serialize>(&mut self, _: &T) -> Result<(), Unsupported> {
+		self.0.push("field".to_owned());
+		Ok(())
+	}
+	fn end(self) -> Result<(), Unsupported> {
+		self.0.push("end".to_owned());
+		Ok(())
+	}
+}
+
+impl ser::SerializeTupleVariant for &mut Recorder {
+	type Ok = ();
+	type Error = Unsupported;
+	fn serialize_field<T: ?Sized + Serialize>(&mut self, _: &T) -> Result<(), Unsupported> {
+		self.0.push("field".to_owned());
+		Ok(())
+	}
+	fn end(self) -> Result<(), Unsupported> {
+		self.0.push("end".to_owned());
+		Ok(())
+	}
+}
+
+impl ser::SerializeStruct for &mut Recorder {
+	type Ok = ();
+	type Error = Unsupported;
+	fn serialize_field<T: ?Sized + Serialize>(
+		&mut self,
+		key: &'static str,
+		_: &T,
+	) -> Result<(), Unsupported> {
+		self.0.push(format!("field {}", key));
+		Ok(())
+	}
+	fn skip_field(&mut self, key: &'static str) -> Result<(), Unsupported> {
+		self.0.push(format!("skip {}", key));
+		Ok(())
+	}
+	fn end(self) -> Result<(), Unsupported> {
+		self.0.push("end".to_owned());
+		Ok(())
+	}
+}
+
+impl ser::SerializeStructVariant for &mut Recorder {
+	type Ok = ();
+	type Error = Unsupported;
+	fn serialize_field<T: ?Sized + Serialize>(
+		&mut self,
+		key: &'static str,
+		_: &T,
+	) -> Result<(), Unsupported> {
+		self.0.push(format!("field {}", key));
+		Ok(())
+	}
+	fn skip_field(&mut self, key: &'static str) -> Result<(), Unsupported> {
+		self.0.push(format!("skip {}", key));
+		Ok(())
+	}
+	fn end(self) -> Result<(), Unsupported> {
+		self.0.push("end".to_owned());
+		Ok(())
+	}
+}
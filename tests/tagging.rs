@@ -0,0 +1,141 @@
+//! Round trips of seeded enums in each tagging mode,
+//! through a self-describing format (JSON) and, where the mode allows it, a sequential one (bincode).
+
+use serde::de::DeserializeSeed;
+use serde_seeded::{seed, seeded, FnDeSeeder, FnSerSeeder};
+
+mod common;
+use common::*;
+
+/// The view lends its arguments, so `scale` is a reference there.
+#[derive(Debug, PartialEq, seed, seeded)]
+#[seed_args(scale: u32)]
+struct Point {
+	#[seeded_de(Scaled(&scale))]
+	#[seeded_ser(Scaled(scale))]
+	x: u32,
+	#[seeded(plain())]
+	y: u32,
+}
+
+macro_rules! tagged {
+	($name:ident $(, #[$attr:meta])*) => {
+		#[derive(Debug, PartialEq, seed, seeded)]
+		#[seed_args(scale: u32)]
+		$(#[$attr])*
+		enum $name {
+			Unit,
+			Newtype(
+				#[seeded_de(FnDeSeeder(|| Point::seed(scale)))]
+				#[seeded_ser(FnSerSeeder::new(|point: &Point| Box::new(point.seeded(*scale))))]
+				Point,
+			),
+			Struct {
+				#[seeded_de(Scaled(&scale))]
+				#[seeded_ser(Scaled(scale))]
+				scaled: u32,
+				// The field of the same name is in scope here.
+				#[seeded(common::plain())]
+				plain: String,
+			},
+		}
+
+		impl $name {
+			fn samples() -> Vec<Self> {
+				vec![
+					Self::Unit,
+					Self::Newtype(Point { x: 40, y: 2 }),
+					Self::Struct {
+						scaled: 60,
+						plain: "plain".to_owned(),
+					},
+				]
+			}
+		}
+	};
+}
+
+tagged!(External);
+tagged!(Internal, #[seed_tag = "type"]);
+tagged!(Adjacent, #[seed_tag = "t"], #[seed_content = "c"]);
+tagged!(Untagged, #[seed_untagged]);
+
+#[test]
+fn external() {
+	let expected = [
+		r#""Unit""#,
+		r#"{"Newtype":{"x":4,"y":2}}"#,
+		r#"{"Struct":{"scaled":6,"plain":"plain"}}"#,
+	];
+	for (value, expected) in External::samples().into_iter().zip(expected.iter()) {
+		let json = to_json(value.seeded(10));
+		assert_eq!(json, *expected);
+		assert_eq!(from_json(External::seed(10), &json), value);
+
+		let bytes = to_bincode(value.seeded(10));
+		assert_eq!(from_bincode(External::seed(10), &bytes), value);
+	}
+}
+
+#[test]
+fn internal() {
+	let expected = [
+		r#"{"type":"Unit"}"#,
+		r#"{"type":"Newtype","x":4,"y":2}"#,
+		r#"{"type":"Struct","scaled":6,"plain":"plain"}"#,
+	];
+	for (value, expected) in Internal::samples().into_iter().zip(expected.iter()) {
+		let json = to_json(value.seeded(10));
+		assert_eq!(json, *expected);
+		assert_eq!(from_json(Internal::seed(10), &json), value);
+	}
+
+	// The tag doesn't have to come first.
+	assert_eq!(
+		from_json(Internal::seed(10), r#"{"x":4,"y":2,"type":"Newtype"}"#),
+		Internal::Newtype(Point { x: 40, y: 2 }),
+	);
+}
+
+#[test]
+fn adjacent() {
+	let expected = [
+		r#"{"t":"Unit"}"#,
+		r#"{"t":"Newtype","c":{"x":4,"y":2}}"#,
+		r#"{"t":"Struct","c":{"scaled":6,"plain":"plain"}}"#,
+	];
+	// Like with serde_derive, the tag is read as identifier, which bincode doesn't support.
+	for (value, expected) in Adjacent::samples().into_iter().zip(expected.iter()) {
+		let json = to_json(value.seeded(10));
+		assert_eq!(json, *expected);
+		assert_eq!(from_json(Adjacent::seed(10), &json), value);
+	}
+
+	// Content before the tag is buffered.
+	assert_eq!(
+		from_json(Adjacent::seed(10), r#"{"c":{"x":4,"y":2},"t":"Newtype"}"#),
+		Adjacent::Newtype(Point { x: 40, y: 2 }),
+	);
+}
+
+#[test]
+fn untagged() {
+	let expected = [
+		r#"null"#,
+		r#"{"x":4,"y":2}"#,
+		r#"{"scaled":6,"plain":"plain"}"#,
+	];
+	for (value, expected) in Untagged::samples().into_iter().zip(expected.iter()) {
+		let json = to_json(value.seeded(10));
+		assert_eq!(json, *expected);
+		assert_eq!(from_json(Untagged::seed(10), &json), value);
+	}
+
+	let error = Untagged::seed(10)
+		.deserialize(&mut serde_json::Deserializer::from_str("[]"))
+		.unwrap_err();
+	assert_eq!(
+		error.to_string(),
+		"data did not match any variant of untagged enum Untagged",
+	);
+}
@@ -0,0 +1,76 @@
+//! Map keys that don't name a field, without flattened fields that could claim them.
+
+use serde::{de::DeserializeSeed, Deserialize};
+use serde_seeded::{
+	content::{Content, ContentRefDeserializer},
+	seed,
+};
+use std::{cell::RefCell, rc::Rc};
+
+mod common;
+use common::*;
+
+#[derive(Debug, PartialEq, seed)]
+struct Lenient {
+	a: u32,
+	b: u32,
+}
+
+#[derive(Debug, PartialEq, seed)]
+#[seed_unknown_fields(deny)]
+struct Strict {
+	a: u32,
+	b: u32,
+}
+
+/// Keeps the unknown entries, which all have numbers as values here.
+fn keep(extra: &mut Vec<(String, u32)>, key: &str, value: Content) {
+	let value = u32::deserialize(ContentRefDeserializer::<serde_json::Error>::new(&value)).unwrap();
+	extra.push((key.to_owned(), value));
+}
+
+#[derive(Debug, PartialEq, seed)]
+#[seed_args(extra: Rc<RefCell<Vec<(String, u32)>>>)]
+#[seed_unknown_fields(with = |key: &str, value| keep(&mut extra.borrow_mut(), key, value))]
+struct Collecting {
+	a: u32,
+	b: u32,
+}
+
+#[test]
+fn ignore() {
+	assert_eq!(
+		from_json(Lenient::seed(), r#"{"c":[3],"a":1,"b":2,"d":{"e":4}}"#),
+		Lenient { a: 1, b: 2 },
+	);
+}
+
+#[test]
+fn deny() {
+	assert_eq!(
+		from_json(Strict::seed(), r#"{"a":1,"b":2}"#),
+		Strict { a: 1, b: 2 },
+	);
+	let error = Strict::seed()
+		.deserialize(&mut serde_json::Deserializer::from_str(
+			r#"{"a":1,"c":3,"b":2}"#,
+		))
+		.unwrap_err();
+	assert_eq!(
+		error.to_string(),
+		"unknown field `c`, expected `a` or `b` at line 1 column 10",
+	);
+}
+
+#[test]
+fn with() {
+	let extra = Rc::new(RefCell::new(vec![]));
+	assert_eq!(
+		from_json(
+			Collecting::seed(Rc::clone(&extra)),
+			r#"{"d":4,"a":1,"c":3,"b":2}"#
+		),
+		Collecting { a: 1, b: 2 },
+	);
+	assert_eq!(*extra.borrow(), [("d".to_owned(), 4), ("c".to_owned(), 3)]);
+}
@@ -0,0 +1,137 @@
+//! Validation of deserialized fields and values against the seed arguments.
+
+use serde::de::DeserializeSeed;
+use serde_seeded::seed;
+
+mod common;
+use common::*;
+
+struct Registry {
+	assets: Vec<&'static str>,
+	checked: usize,
+}
+
+fn known_asset(asset: &String, registry: &mut Registry, _limit: &u8) -> Result<(), String> {
+	registry.checked += 1;
+	if registry.assets.contains(&asset.as_str()) {
+		Ok(())
+	} else {
+		Err(format!("unknown asset {:?}", asset))
+	}
+}
+
+fn in_range(range: &Range, _registry: &mut Registry, limit: &u8) -> Result<(), String> {
+	if range.min > range.max {
+		Err("min is greater than max".to_owned())
+	} else if range.max > *limit {
+		Err(format!("max is greater than {}", limit))
+	} else {
+		Ok(())
+	}
+}
+
+#[derive(Debug, PartialEq, seed)]
+#[seed_args(registry: &mut Registry, limit: u8)]
+#[seed_validate(in_range)]
+struct Range {
+	min: u8,
+	max: u8,
+	#[seeded_de(validate = known_asset)]
+	asset: String,
+}
+
+fn read(registry: &mut Registry, json: &str) -> Result<Range, String> {
+	Range::seed(registry, 10)
+		.deserialize(&mut serde_json::Deserializer::from_str(json))
+		.map_err(|error| error.to_string())
+}
+
+#[test]
+fn validated() {
+	let mut registry = Registry {
+		assets: vec!["rock"],
+		checked: 0,
+	};
+
+	let range = Range {
+		min: 1,
+		max: 2,
+		asset: "rock".to_owned(),
+	};
+	assert_eq!(
+		read(&mut registry, r#"{"min":1,"max":2,"asset":"rock"}"#),
+		Ok(range)
+	);
+	assert_eq!(
+		from_bincode(
+			Range::seed(&mut registry, 10),
+			&to_bincode((1_u8, 2_u8, "rock"))
+		),
+		Range {
+			min: 1,
+			max: 2,
+			asset: "rock".to_owned(),
+		},
+	);
+	assert_eq!(registry.checked, 2);
+}
+
+#[test]
+fn rejected() {
+	let mut registry = Registry {
+		assets: vec!["rock"],
+		checked: 0,
+	};
+
+	assert_eq!(
+		read(&mut registry, r#"{"min":1,"max":2,"asset":"tree"}"#),
+		Err(r#"unknown asset "tree" at line 1 column 32"#.to_owned()),
+	);
+	assert_eq!(
+		read(&mut registry, r#"[3,2,"rock"]"#),
+		Err("min is greater than max at line 1 column 12".to_owned()),
+	);
+	// The validator sees the seed arguments.
+	assert_eq!(
+		read(&mut registry, r#"{"min":1,"max":20,"asset":"rock"}"#),
+		Err("max is greater than 10 at line 1 column 33".to_owned()),
+	);
+	assert_eq!(registry.checked, 3);
+}
+
+#[derive(Debug, PartialEq, seed)]
+#[seed_args(registry: &mut Registry, limit: u8)]
+#[seed_tag = "type"]
+enum Placed {
+	Asset(#[seeded_de(validate = known_asset)] String),
+	Range {
+		#[seeded_de(validate = known_asset)]
+		asset: String,
+	},
+}
+
+#[test]
+fn variants() {
+	let mut registry = Registry {
+		assets: vec!["rock"],
+		checked: 0,
+	};
+	let read = |registry: &mut Registry, json: &str| {
+		Placed::seed(registry, 10)
+			.deserialize(&mut serde_json::Deserializer::from_str(json))
+			.map_err(|error| error.to_string())
+	};
+
+	assert_eq!(
+		read(&mut registry, r#"{"type":"Range","asset":"rock"}"#),
+		Ok(Placed::Range {
+			asset: "rock".to_owned()
+		}),
+	);
+	// Internally tagged content is buffered, so the error can't point into the input.
+	assert_eq!(
+		read(&mut registry, r#"{"type":"Range","asset":"tree"}"#),
+		Err(r#"unknown asset "tree""#.to_owned()),
+	);
+	assert_eq!(registry.checked, 2);
+}
@@ -0,0 +1,175 @@
+//! Round trips of versioned types, and how older versions of the input are read and migrated.
+
+use serde::de::DeserializeSeed;
+use serde_seeded::{seed, seeded};
+
+mod common;
+use common::*;
+
+/// Records which migrations ran.
+struct Log(Vec<&'static str>);
+
+fn hp_to_health(save: &mut Save, log: &mut Log) -> Result<(), String> {
+	save.health = save.hp * 10;
+	log.0.push("hp_to_health");
+	Ok(())
+}
+
+fn name_anonymous(save: &mut Save, log: &mut Log) -> Result<(), String> {
+	save.name = "anonymous".to_owned();
+	log.0.push("name_anonymous");
+	Ok(())
+}
+
+#[derive(Debug, PartialEq, seed, seeded)]
+#[seed_args_de(log: &mut Log)]
+#[seed_version(3)]
+#[seed_migrate(from = 1, with = hp_to_health)]
+#[seed_migrate(from = 2, with = name_anonymous)]
+struct Save {
+	#[seeded(until = 2)]
+	hp: u32,
+	#[seeded(since = 2)]
+	health: u32,
+	#[seeded(since = 3)]
+	name: String,
+}
+
+#[test]
+fn header() {
+	let save = Save {
+		hp: 0,
+		health: 50,
+		name: "me".to_owned(),
+	};
+	let mut log = Log(vec![]);
+
+	let json = to_json(save.seeded());
+	assert_eq!(json, r#"[3,{"health":50,"name":"me"}]"#);
+	assert_eq!(from_json(Save::seed(&mut log), &json), save);
+
+	let bytes = to_bincode(save.seeded());
+	assert_eq!(bytes, to_bincode((3_u32, (50_u32, "me"))));
+	assert_eq!(from_bincode(Save::seed(&mut log), &bytes), save);
+
+	// The current version doesn't need migrating.
+	assert!(log.0.is_empty());
+}
+
+#[test]
+fn migrations() {
+	let mut log = Log(vec![]);
+	let expected = Save {
+		hp: 4,
+		health: 40,
+		name: "anonymous".to_owned(),
+	};
+	assert_eq!(from_json(Save::seed(&mut log), r#"[1,{"hp":4}]"#), expected);
+	assert_eq!(log.0, ["hp_to_health", "name_anonymous"]);
+
+	let mut log = Log(vec![]);
+	assert_eq!(
+		from_bincode(Save::seed(&mut log), &to_bincode((1_u32, (4_u32,)))),
+		expected,
+	);
+	assert_eq!(log.0, ["hp_to_health", "name_anonymous"]);
+
+	// Only the migrations from the input's version onwards run.
+	let mut log = Log(vec![]);
+	assert_eq!(
+		from_json(Save::seed(&mut log), r#"[2,[40]]"#),
+		Save {
+			hp: 0,
+			health: 40,
+			name: "anonymous".to_owned(),
+		},
+	);
+	assert_eq!(log.0, ["name_anonymous"]);
+}
+
+#[test]
+fn errors() {
+	let error = |json: &str| {
+		Save::seed(&mut Log(vec![]))
+			.deserialize(&mut serde_json::Deserializer::from_str(json))
+			.unwrap_err()
+			.to_string()
+	};
+	assert_eq!(
+		error(r#"[4,{}]"#),
+		"invalid value: integer `4`, expected a version up to 3 at line 1 column 3",
+	);
+	// Lengths are those of the input's version.
+	assert_eq!(
+		error(r#"[3,[5,"x",7]]"#),
+		"invalid length 3, expected struct Save with 2 elements at line 1 column 12",
+	);
+	assert_eq!(
+		error(r#"[1,[]]"#),
+		"invalid length 0, expected struct Save with 1 element, but field `hp` is missing at line 1 column 5",
+	);
+}
+
+#[derive(Debug, PartialEq, seed, seeded)]
+#[seed_args(version: u32)]
+#[seed_version(2, arg = version)]
+struct Point(u8, #[seeded(since = 2, default = 7)] u8);
+
+#[test]
+fn argument() {
+	let point = Point(1, 2);
+
+	// The version isn't written, since the reader gets it from the same place.
+	let json = to_json(point.seeded(2));
+	assert_eq!(json, "[1,2]");
+	assert_eq!(from_json(Point::seed(2), &json), point);
+
+	let bytes = to_bincode(point.seeded(2));
+	assert_eq!(from_bincode(Point::seed(2), &bytes), point);
+
+	assert_eq!(from_json(Point::seed(1), "[1]"), Point(1, 7));
+	assert_eq!(from_bincode(Point::seed(1), &[1]), Point(1, 7));
+}
+
+#[derive(Debug, PartialEq, seed)]
+#[seed_args(version: u32)]
+#[seed_version(2, arg = version)]
+#[seed_unknown_fields(deny)]
+struct Strict {
+	#[seeded(until = 2)]
+	old: u8,
+	#[seeded(since = 2)]
+	new: u8,
+}
+
+#[test]
+fn other_versions_fields() {
+	// Keys of fields the input's version doesn't have are unknown, so they're ignored by default...
+	let mut log = Log(vec![]);
+	assert_eq!(
+		from_json(
+			Save::seed(&mut log),
+			r#"[3,{"hp":4,"health":50,"name":"me"}]"#
+		),
+		Save {
+			hp: 0,
+			health: 50,
+			name: "me".to_owned(),
+		},
+	);
+
+	// ...and rejected where unknown fields are.
+	assert_eq!(
+		from_json(Strict::seed(1), r#"{"old":1}"#),
+		Strict { old: 1, new: 0 }
+	);
+	let error = Strict::seed(1)
+		.deserialize(&mut serde_json::Deserializer::from_str(
+			r#"{"old":1,"new":2}"#,
+		))
+		.unwrap_err();
+	assert_eq!(
+		error.to_string(),
+		"unknown field `new`, expected `old` or `new` at line 1 column 14",
+	);
+}
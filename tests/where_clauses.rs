@@ -0,0 +1,73 @@
+//! Round trips through seeds and views whose generic parameters are bounded with `seed_where`,
+//! separately for deserialization and serialization where they differ.
+
+use serde_seeded::{seed, seeded, DeSeeder, Seeded, SerSeeder};
+
+mod common;
+use common::*;
+
+trait Context {
+	fn scale(&self) -> &u32;
+}
+
+struct Shop;
+
+impl Context for Shop {
+	fn scale(&self) -> &u32 {
+		&100
+	}
+}
+
+/// Only writes values, halving them, and isn't [`Clone`].
+struct Halved;
+
+impl SerSeeder<u32> for Halved {
+	fn seeded<'s>(&'s self, value: &'s u32) -> Seeded<'s> {
+		Box::new(value / 2)
+	}
+}
+
+#[derive(Debug, PartialEq, seed, seeded)]
+#[seed_generics(C, S)]
+#[seed_where(C: Context)]
+#[seed_where_de(S: DeSeeder<'de, u32> + Clone)]
+#[seed_where_ser(S: SerSeeder<u32>)]
+#[seed_args(context: C, seeder: S)]
+struct Prices {
+	#[seeded(Scaled(context.scale()))]
+	net: u32,
+	#[seeded_de(seeder.clone())]
+	#[seeded_ser(seeder)]
+	gross: u32,
+}
+
+#[test]
+fn round_trip() {
+	let prices = Prices {
+		net: 500,
+		gross: 600,
+	};
+
+	let json = to_json(prices.seeded(Shop, Scaled(&10)));
+	assert_eq!(json, r#"{"net":5,"gross":60}"#);
+	assert_eq!(from_json(Prices::seed(Shop, Scaled(&10)), &json), prices);
+
+	let bytes = to_bincode(prices.seeded(Shop, Scaled(&10)));
+	assert_eq!(
+		from_bincode(Prices::seed(Shop, Scaled(&10)), &bytes),
+		prices
+	);
+}
+
+#[test]
+fn serialization_bounds() {
+	// `Halved` can't deserialize, but doesn't have to for serialization.
+	let prices = Prices {
+		net: 500,
+		gross: 600,
+	};
+	assert_eq!(
+		to_json(prices.seeded(Shop, Halved)),
+		r#"{"net":5,"gross":300}"#,
+	);
+}